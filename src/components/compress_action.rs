@@ -0,0 +1,195 @@
+use dioxus::prelude::*;
+use web_sys::Url;
+
+use crate::{
+    error::Error,
+    pdf::{CompressDocument, CompressOptions},
+    utils::{convert_vec_u8_to_pdf_blob, file_size_message},
+};
+
+use super::input_file::FileUploaded;
+
+#[derive(PartialEq, Clone)]
+struct CompressedFile {
+    filename: String,
+    object_url: String,
+    before_size: usize,
+    after_size: usize,
+}
+
+#[derive(PartialEq, Clone, Props)]
+pub struct CompressActionProps {
+    files_uploaded: Signal<Vec<FileUploaded>>,
+    // Compressed file shown in the preview
+    preview_object_url: Signal<String>,
+    error_message: Signal<String>,
+}
+
+#[component]
+pub fn CompressAction(mut props: CompressActionProps) -> Element {
+    let default_options = CompressOptions::default();
+    let mut target_dpi_str = use_signal(|| default_options.target_dpi.to_string());
+    let mut jpeg_quality_str = use_signal(|| default_options.jpeg_quality.to_string());
+    let mut compressed_files: Signal<Vec<CompressedFile>> = use_signal(Vec::new);
+    let mut revoke_compressed_files = move || {
+        props.preview_object_url.set(String::new());
+        for compressed_file in compressed_files.write().drain(..) {
+            let _revoke_url = Url::revoke_object_url(&compressed_file.object_url);
+        }
+    };
+    // Results of other files are stale
+    use_effect(move || {
+        props.files_uploaded.read();
+        revoke_compressed_files();
+    });
+
+    let compress_file_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        revoke_compressed_files();
+        let options = match (
+            target_dpi_str.read().trim().parse::<u32>(),
+            jpeg_quality_str.read().trim().parse::<u8>(),
+        ) {
+            (Ok(target_dpi), Ok(jpeg_quality)) => CompressOptions {
+                target_dpi,
+                jpeg_quality,
+            },
+            _ => {
+                props
+                    .error_message
+                    .set("DPI and quality must be numbers".to_string());
+                return;
+            }
+        };
+        for file_uploaded in props.files_uploaded.read().iter() {
+            if file_uploaded.is_locked {
+                props.error_message.set(format!(
+                    "{} is password protected, enter its password first",
+                    file_uploaded.filename
+                ));
+                return;
+            }
+            let document =
+                CompressDocument::compress_pdf_from_mem(&file_uploaded.file_buffer, &options);
+            match document {
+                Ok(mut doc) => {
+                    let mut buffer: Vec<u8> = Vec::new();
+                    if let Err(std_io_e) = doc.save_to(&mut buffer) {
+                        props
+                            .error_message
+                            .set(format!("Error (compress, save): {std_io_e}"));
+                        return;
+                    }
+                    let result_url = convert_vec_u8_to_pdf_blob(&buffer).and_then(|blob| {
+                        Url::create_object_url_with_blob(&blob).map_err(|js_value| {
+                            Error::JsValue(format!("Error (compress, object_url): {js_value:?}"))
+                        })
+                    });
+                    match result_url {
+                        Ok(url) => {
+                            if props.preview_object_url.read().is_empty() {
+                                props.preview_object_url.set(url.clone());
+                            }
+                            compressed_files.write().push(CompressedFile {
+                                filename: format!(
+                                    "{}_compressed.pdf",
+                                    file_uploaded.get_filename()
+                                ),
+                                object_url: url,
+                                before_size: file_uploaded.file_buffer.len(),
+                                after_size: buffer.len(),
+                            })
+                        }
+                        Err(e) => {
+                            props
+                                .error_message
+                                .set(format!("Error (compress, result): {e}"));
+                            return;
+                        }
+                    }
+                }
+                Err(pdf_compress_e) => {
+                    match pdf_compress_e {
+                        crate::pdf::Error::Lopdf(error) => props
+                            .error_message
+                            .set(format!("Error::Lopdf (compress, compress): {error}")),
+                        crate::pdf::Error::EncryptedDocument => props
+                            .error_message
+                            .set("a PDF file is password protected".to_string()),
+                        crate::pdf::Error::InValidCompressOptions => props
+                            .error_message
+                            .set("DPI must be above 0 and quality between 1 and 100".to_string()),
+                        _ => props
+                            .error_message
+                            .set(format!("Error (compress, compress): {pdf_compress_e}")),
+                    }
+                    return;
+                }
+            }
+        }
+    };
+    rsx! {
+        div { class: "mt-4 flex w-full flex-col items-center rounded-md p-4",
+            div { class: "flex w-full max-w-screen-sm flex-row items-center justify-center gap-2 text-sm sm:text-base",
+                label { class: "text-nowrap", r#for: "target_dpi", "Image DPI: " }
+                input {
+                    id: "target_dpi",
+                    class: "w-20 rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "number",
+                    min: "1",
+                    title: "Images shown at a higher resolution are downsampled",
+                    value: target_dpi_str,
+                    oninput: move |evt: Event<FormData>| target_dpi_str.set(evt.value()),
+                }
+                label { class: "text-nowrap", r#for: "jpeg_quality", "JPEG quality: " }
+                input {
+                    id: "jpeg_quality",
+                    class: "w-20 rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "number",
+                    min: "1",
+                    max: "100",
+                    title: "Lower is smaller, 1 to 100",
+                    value: jpeg_quality_str,
+                    oninput: move |evt: Event<FormData>| jpeg_quality_str.set(evt.value()),
+                }
+            }
+            button {
+                id: "compress",
+                class: "mt-2 w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                onclick: compress_file_handler,
+                "Compress PDF"
+            }
+            ul { class: "mt-3 flex w-full max-w-screen-sm flex-col",
+                for compressed_file in compressed_files.read().iter() {
+                    {
+                        let CompressedFile { filename, object_url, before_size, after_size } = compressed_file.clone();
+                        rsx! {
+                            li {
+                                key: "{object_url}",
+                                class: "my-1 flex flex-row items-center justify-between gap-2 text-xs sm:text-sm md:text-base",
+                                button {
+                                    class: "truncate hover:text-red-500 focus:text-red-500",
+                                    title: "Preview {filename}",
+                                    onclick: {
+                                        let object_url = object_url.clone();
+                                        move |_evt| props.preview_object_url.set(object_url.clone())
+                                    },
+                                    "{filename}"
+                                }
+                                span { class: "text-nowrap text-neutral-500",
+                                    {format!("{} → {}", file_size_message(before_size), file_size_message(after_size))}
+                                }
+                                a {
+                                    class: "rounded-md p-2 bg-sky-200 hover:bg-sky-400 focus:bg-sky-400",
+                                    href: "{object_url}",
+                                    download: "{filename}",
+                                    "Download"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use dioxus::prelude::*;
+use web_sys::Url;
+
+use crate::{
+    pdf::{ConvertDocument, ImagePageOptions, PageOrientation, PageSize},
+    utils::save_to_object_url,
+};
+
+use super::input_file::FileUploaded;
+
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+#[derive(PartialEq, Clone, Props)]
+pub struct ImagesToPdfActionProps {
+    images_file_name: Signal<String>,
+    images_file_object_url: Signal<String>,
+    files_uploaded: Signal<Vec<FileUploaded>>,
+    error_message: Signal<String>,
+}
+
+#[component]
+pub fn ImagesToPdfAction(mut props: ImagesToPdfActionProps) -> Element {
+    let mut page_size = use_signal(|| PageSize::A4);
+    let mut orientation = use_signal(|| PageOrientation::Auto);
+    let mut margin_str = use_signal(|| "0".to_string());
+
+    let convert_file_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        let Ok(margin) = margin_str.read().trim().parse::<f32>() else {
+            props
+                .error_message
+                .set("the margin must be a number".to_string());
+            return;
+        };
+        let options = ImagePageOptions {
+            page_size: page_size(),
+            orientation: orientation(),
+            margin: margin * POINTS_PER_MM,
+        };
+        let files_uploaded = props.files_uploaded.read();
+        let images: Vec<(&str, &[u8])> = files_uploaded
+            .iter()
+            .filter_map(|file_uploaded| {
+                file_uploaded
+                    .image_buffer
+                    .as_ref()
+                    .map(|image_buffer| (file_uploaded.filename.as_str(), image_buffer.as_slice()))
+            })
+            .collect();
+        let document = ConvertDocument::images_to_pdf_from_mem(&images, &options);
+        match document {
+            Ok(mut doc) => {
+                if !props.images_file_object_url.read().is_empty() {
+                    let _revoke_url = Url::revoke_object_url(&props.images_file_object_url.read());
+                }
+                match save_to_object_url(&mut doc) {
+                    Ok(url) => {
+                        props.images_file_object_url.set(url);
+                        props.images_file_name.set("images.pdf".to_string());
+                    }
+                    Err(e) => props
+                        .error_message
+                        .set(format!("Error (images_to_pdf, save): {e}")),
+                }
+            }
+            Err(pdf_convert_e) => match pdf_convert_e {
+                crate::pdf::Error::ImageListIsEmpty => {
+                    props.error_message.set("No images to convert".to_string())
+                }
+                crate::pdf::Error::CannotReadImage(filename) => props
+                    .error_message
+                    .set(format!("{filename} is not a JPEG or PNG image")),
+                crate::pdf::Error::InValidImagePageOptions => props
+                    .error_message
+                    .set("the margins leave no room for the images".to_string()),
+                _ => props
+                    .error_message
+                    .set(format!("Error (images_to_pdf, convert): {pdf_convert_e}")),
+            },
+        }
+    };
+    rsx! {
+        div { class: "mt-4 flex w-auto flex-col items-center rounded-md p-4",
+            div { class: "flex w-full flex-row flex-wrap items-center justify-center gap-2 text-sm sm:text-base",
+                label { r#for: "page_size", "Page size: " }
+                select {
+                    id: "page_size",
+                    class: "rounded-md border-2 border-neutral-100 p-2",
+                    onchange: move |evt: Event<FormData>| {
+                        page_size
+                            .set(
+                                match evt.value().as_str() {
+                                    "letter" => PageSize::Letter,
+                                    "fit" => PageSize::Fit,
+                                    _ => PageSize::A4,
+                                },
+                            )
+                    },
+                    option { value: "a4", selected: page_size() == PageSize::A4, "A4" }
+                    option { value: "letter", selected: page_size() == PageSize::Letter, "Letter" }
+                    option { value: "fit", selected: page_size() == PageSize::Fit, "Image size" }
+                }
+                if page_size() != PageSize::Fit {
+                    label { r#for: "orientation", "Orientation: " }
+                    select {
+                        id: "orientation",
+                        class: "rounded-md border-2 border-neutral-100 p-2",
+                        onchange: move |evt: Event<FormData>| {
+                            orientation
+                                .set(
+                                    match evt.value().as_str() {
+                                        "portrait" => PageOrientation::Portrait,
+                                        "landscape" => PageOrientation::Landscape,
+                                        _ => PageOrientation::Auto,
+                                    },
+                                )
+                        },
+                        option {
+                            value: "auto",
+                            selected: orientation() == PageOrientation::Auto,
+                            "Auto"
+                        }
+                        option {
+                            value: "portrait",
+                            selected: orientation() == PageOrientation::Portrait,
+                            "Portrait"
+                        }
+                        option {
+                            value: "landscape",
+                            selected: orientation() == PageOrientation::Landscape,
+                            "Landscape"
+                        }
+                    }
+                }
+                label { r#for: "margin", "Margin (mm): " }
+                input {
+                    id: "margin",
+                    class: "w-20 rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "number",
+                    min: "0",
+                    value: margin_str,
+                    oninput: move |evt: Event<FormData>| margin_str.set(evt.value()),
+                }
+            }
+            button {
+                id: "images_to_pdf",
+                class: "mt-2 w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                onclick: convert_file_handler,
+                "Convert to PDF"
+            }
+            div { class: "mt-3 flex flex-col w-full max-w-screen-sm",
+                if !props.images_file_name.read().is_empty() {
+                    label { class: "text-sm pl-2", r#for: "images_filename", "Filename" }
+                    div { class: "flex justify-between gap-2",
+                        input {
+                            id: "images_filename",
+                            class: "text-base rounded-md border-2 w-full border-neutral-100 p-2",
+                            r#type: "text",
+                            value: props.images_file_name,
+                            oninput: move |evt: Event<FormData>| props.images_file_name.set(evt.value()),
+                        }
+                        a {
+                            class: "rounded-md p-2 bg-sky-200 hover:bg-sky-400 focus:bg-sky-400",
+                            href: props.images_file_object_url,
+                            download: props.images_file_name,
+                            "Download"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
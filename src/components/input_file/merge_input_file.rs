@@ -1,43 +1,68 @@
-use dioxus::prelude::*;
+use dioxus::{html::FileEngine, prelude::*};
+use std::sync::Arc;
 
-use crate::{components::input_file::FileUploaded, utils::convert_vec_u8_to_pdf_blob};
+use crate::components::input_file::{is_image_filename, FileUploaded};
 
 #[derive(PartialEq, Clone, Props)]
 pub struct MergeInputFileProps {
     files_uploaded: Signal<Vec<FileUploaded>>,
     merge_file_name: Signal<String>,
     error_message: Signal<String>,
+    // Only JPEG and PNG images, PDF files and ZIP archives are refused
+    #[props(default)]
+    is_images_only: bool,
 }
 
-#[component]
-pub fn MergeInputFile(mut props: MergeInputFileProps) -> Element {
-    let upload_file_handler = move |evt: Event<FormData>| async move {
-        if let Some(file_engine) = &evt.files() {
-            let files = file_engine.files();
-            for filename in files {
-                if let Some(file_buffer) = file_engine.read_file(&filename).await {
-                    let file_data_blob = convert_vec_u8_to_pdf_blob(&file_buffer);
-                    match file_data_blob {
-                        Ok(file_data_blob) => {
-                            let result_file_uploaded =
-                                FileUploaded::new(filename, file_data_blob, file_buffer);
-                            match result_file_uploaded {
-                                Ok(file_uploaded) => {
-                                    props.files_uploaded.write().push(file_uploaded);
-                                    props.merge_file_name.write().clear();
-                                }
-                                Err(e) => props
-                                    .error_message
-                                    .set(format!("Error (merge_input_file, 34): {e}")),
-                            }
-                        }
-                        Err(e) => props
-                            .error_message
-                            .set(format!("Error (merge_input_file, 39): {e}")),
+// Add the uploaded files, picked or dropped: PDF files, JPEG and PNG images and the PDF files
+// of ZIP archives, or the images only
+pub async fn upload_files(
+    file_engine: Arc<dyn FileEngine>,
+    mut files_uploaded: Signal<Vec<FileUploaded>>,
+    mut merge_file_name: Signal<String>,
+    mut error_message: Signal<String>,
+    is_images_only: bool,
+) {
+    for filename in file_engine.files() {
+        if is_images_only && !is_image_filename(&filename) {
+            error_message.set(format!("{filename} is not a JPEG or PNG image"));
+            continue;
+        }
+        if let Some(file_buffer) = file_engine.read_file(&filename).await {
+            match FileUploaded::from_upload_many(filename.clone(), file_buffer) {
+                Ok((files, skipped_paths)) => {
+                    files_uploaded.write().extend(files);
+                    merge_file_name.write().clear();
+                    if !skipped_paths.is_empty() {
+                        error_message.set(format!(
+                            "skipped files of {filename} that are not PDF files: {}",
+                            skipped_paths.join(", ")
+                        ));
                     }
                 }
+                Err(e) => error_message.set(format!("Error (merge_input_file, upload): {e}")),
             }
         }
+    }
+}
+
+#[component]
+pub fn MergeInputFile(props: MergeInputFileProps) -> Element {
+    let upload_file_handler = move |evt: Event<FormData>| async move {
+        if let Some(file_engine) = evt.files() {
+            upload_files(
+                file_engine,
+                props.files_uploaded,
+                props.merge_file_name,
+                props.error_message,
+                props.is_images_only,
+            )
+            .await;
+        }
+    };
+    let accept = if props.is_images_only {
+        ".jpg,.jpeg,.png"
+    } else {
+        ".pdf,.jpg,.jpeg,.png,.zip"
     };
 
     rsx! {
@@ -47,7 +72,7 @@ pub fn MergeInputFile(mut props: MergeInputFileProps) -> Element {
             // tell the input to pick a file
             r#type: "file",
             // list the accepted extensions
-            accept: ".pdf",
+            accept,
             // pick multiple files
             multiple: true,
             onchange: upload_file_handler,
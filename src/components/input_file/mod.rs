@@ -4,7 +4,29 @@ pub mod split_input_file;
 use dioxus::prelude::*;
 use web_sys::{Blob, Url};
 
-use crate::error::Error;
+use crate::{
+    error::Error,
+    pdf::{ConvertDocument, DecryptDocument, ImagePageOptions},
+    utils::{convert_vec_u8_to_pdf_blob, read_zip_pdf_files},
+};
+
+// Images are wrapped in a page when uploaded
+const IMAGE_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+
+pub fn is_image_filename(filename: &str) -> bool {
+    filename
+        .rsplit_once('.')
+        .is_some_and(|(_, extension)| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+pub fn is_pdf_filename(filename: &str) -> bool {
+    filename.to_lowercase().ends_with(".pdf")
+}
+
+// ZIP archives are expanded into their PDF files when uploaded
+pub fn is_zip_filename(filename: &str) -> bool {
+    filename.to_lowercase().ends_with(".zip")
+}
 
 #[derive(PartialEq, Props, Clone)]
 pub struct InputFileProps {
@@ -32,19 +54,122 @@ pub struct FileUploaded {
     pub filename: String,
     pub url: String,
     pub file_buffer: Vec<u8>,
+    // Pages selected for merging, e.g. "1-3, 7", empty for all pages
+    pub page_range: String,
+    // Encrypted with a user password, `unlock` has to be called before using the file
+    pub is_locked: bool,
+    // The uploaded JPEG or PNG image, `file_buffer` is the image wrapped in a page
+    pub image_buffer: Option<Vec<u8>>,
 }
 
 impl FileUploaded {
     pub fn new(filename: String, blob: Blob, file_buffer: Vec<u8>) -> Result<Self, Error> {
         let url = Url::create_object_url_with_blob(&blob)
             .map_err(|_| Error::JsValue("Could not create object URL".to_string()))?;
+        let mut file_uploaded = Self {
+            filename,
+            url,
+            file_buffer,
+            page_range: String::new(),
+            is_locked: false,
+            image_buffer: None,
+        };
+        // Documents protected with an owner password only are opened without asking
+        if DecryptDocument::is_encrypted_from_mem(&file_uploaded.file_buffer) {
+            file_uploaded.is_locked = file_uploaded.unlock("").is_err();
+        }
+
+        Ok(file_uploaded)
+    }
+
+    // PDF files as they are, JPEG and PNG images wrapped in a page
+    pub fn from_upload(filename: String, file_buffer: Vec<u8>) -> Result<Self, Error> {
+        if is_image_filename(&filename) {
+            Self::from_image(filename, file_buffer)
+        } else {
+            let blob = convert_vec_u8_to_pdf_blob(&file_buffer)?;
+            Self::new(filename, blob, file_buffer)
+        }
+    }
+
+    // Every PDF file of the ZIP archive sorted by path, and the paths of the other files skipped
+    pub fn from_zip(buffer: &[u8]) -> Result<(Vec<Self>, Vec<String>), Error> {
+        let (pdf_files, skipped_paths) = read_zip_pdf_files(buffer)?;
+        let mut files_uploaded: Vec<Self> = Vec::new();
+        for (filename, file_buffer) in pdf_files {
+            let blob = convert_vec_u8_to_pdf_blob(&file_buffer)?;
+            files_uploaded.push(Self::new(filename, blob, file_buffer)?);
+        }
+
+        Ok((files_uploaded, skipped_paths))
+    }
+
+    // The files of an upload: the PDF files of a ZIP archive with the paths of the other files
+    // skipped, or the PDF file or image itself
+    pub fn from_upload_many(
+        filename: String,
+        file_buffer: Vec<u8>,
+    ) -> Result<(Vec<Self>, Vec<String>), Error> {
+        if is_zip_filename(&filename) {
+            Self::from_zip(&file_buffer)
+        } else {
+            Ok((vec![Self::from_upload(filename, file_buffer)?], Vec::new()))
+        }
+    }
+
+    // Wrap the image in an A4 page so it can be mixed with PDF files
+    pub fn from_image(filename: String, image_buffer: Vec<u8>) -> Result<Self, Error> {
+        let mut doc = ConvertDocument::images_to_pdf_from_mem(
+            &[(&filename, &image_buffer)],
+            &ImagePageOptions::default(),
+        )
+        .map_err(|e| Error::Pdf(e.to_string()))?;
+        let mut file_buffer: Vec<u8> = Vec::new();
+        doc.save_to(&mut file_buffer)
+            .map_err(|e| Error::Pdf(e.to_string()))?;
+        let blob = convert_vec_u8_to_pdf_blob(&file_buffer)?;
+        let mut file_uploaded = Self::new(filename, blob, file_buffer)?;
+        file_uploaded.image_buffer = Some(image_buffer);
+
+        Ok(file_uploaded)
+    }
+
+    // A result that is not a PDF, e.g. a rendered page
+    pub fn from_result(filename: String, blob: Blob, file_buffer: Vec<u8>) -> Result<Self, Error> {
+        let url = Url::create_object_url_with_blob(&blob)
+            .map_err(|_| Error::JsValue("Could not create object URL".to_string()))?;
+
         Ok(Self {
             filename,
             url,
             file_buffer,
+            page_range: String::new(),
+            is_locked: false,
+            image_buffer: None,
         })
     }
 
+    // Decrypt the file with the password and replace the buffer and object URL with the decrypted PDF
+    pub fn unlock(&mut self, password: &str) -> Result<(), Error> {
+        let mut doc =
+            DecryptDocument::load_mem(&self.file_buffer, password).map_err(|e| match e {
+                crate::pdf::Error::EncryptedDocument => Error::IncorrectPassword,
+                e => Error::Pdf(e.to_string()),
+            })?;
+        let mut file_buffer: Vec<u8> = Vec::new();
+        doc.save_to(&mut file_buffer)
+            .map_err(|e| Error::Pdf(e.to_string()))?;
+        let blob = convert_vec_u8_to_pdf_blob(&file_buffer)?;
+        let url = Url::create_object_url_with_blob(&blob)
+            .map_err(|_| Error::JsValue("Could not create object URL".to_string()))?;
+
+        self.revoke_object_url();
+        self.url = url;
+        self.file_buffer = file_buffer;
+        self.is_locked = false;
+        Ok(())
+    }
+
     pub fn get_url(&self) -> String {
         self.url.clone()
     }
@@ -53,8 +178,13 @@ impl FileUploaded {
         let _revoke_oveject_url = Url::revoke_object_url(&self.url);
     }
 
+    // The filename without its extension
     pub fn get_filename(&self) -> String {
-        self.filename[..self.filename.len() - 4].to_string()
+        self.filename
+            .rsplit_once('.')
+            .map(|(stem, _)| stem)
+            .unwrap_or(&self.filename)
+            .to_string()
     }
 }
 
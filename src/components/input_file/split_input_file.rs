@@ -1,8 +1,9 @@
-use dioxus::prelude::*;
+use dioxus::{html::FileEngine, prelude::*};
+use std::sync::Arc;
 
 use crate::utils::convert_vec_u8_to_pdf_blob;
 
-use super::FileUploaded;
+use super::{is_pdf_filename, FileUploaded};
 
 #[derive(PartialEq, Clone, Props)]
 pub struct SplitInputFileProps {
@@ -12,39 +13,54 @@ pub struct SplitInputFileProps {
     error_message: Signal<String>,
 }
 
+// Replace the uploaded file with the first PDF file picked or dropped, the other files are refused
+pub async fn upload_file(
+    file_engine: Arc<dyn FileEngine>,
+    mut file_uploaded: Signal<Option<FileUploaded>>,
+    mut files_uploaded: Signal<Vec<FileUploaded>>,
+    mut object_url: Signal<String>,
+    mut error_message: Signal<String>,
+) {
+    let Some(filename) = file_engine.files().into_iter().next() else {
+        return;
+    };
+    if !is_pdf_filename(&filename) {
+        error_message.set(format!("{filename} is not a PDF file"));
+        return;
+    }
+    let Some(file_buffer) = file_engine.read_file(&filename).await else {
+        return;
+    };
+    for old_file in files_uploaded.read().iter() {
+        old_file.revoke_object_url();
+    }
+    files_uploaded.write().clear();
+    let result = convert_vec_u8_to_pdf_blob(&file_buffer)
+        .and_then(|blob| FileUploaded::new(filename, blob, file_buffer));
+    match result {
+        Ok(new_file_uploaded) => {
+            if let Some(current_file_uploaded) = file_uploaded.read().as_ref() {
+                current_file_uploaded.revoke_object_url();
+            }
+            object_url.set(new_file_uploaded.get_url());
+            file_uploaded.set(Some(new_file_uploaded));
+        }
+        Err(e) => error_message.set(format!("Error (split_input_file, upload): {e}")),
+    }
+}
+
 #[component]
-pub fn SplitInputFile(mut props: SplitInputFileProps) -> Element {
+pub fn SplitInputFile(props: SplitInputFileProps) -> Element {
     let upload_file_handler = move |evt: Event<FormData>| async move {
-        if let Some(file_engine) = &evt.files() {
-            for old_file in props.files_uploaded.read().clone() {
-                old_file.revoke_object_url();
-            }
-            props.files_uploaded.write().clear();
-            let files = file_engine.files();
-            for filename in files {
-                if let Some(file_buffer) = file_engine.read_file(&filename).await {
-                    let file_data_blob = convert_vec_u8_to_pdf_blob(&file_buffer);
-                    match file_data_blob {
-                        Ok(file_data_blob) => {
-                            let result_file_uploaded =
-                                FileUploaded::new(filename, file_data_blob, file_buffer);
-                            match result_file_uploaded {
-                                Ok(file_uploaded) => {
-                                    if let Some(current_file_uploaded) =
-                                        props.file_uploaded.read().clone()
-                                    {
-                                        current_file_uploaded.revoke_object_url();
-                                    }
-                                    props.object_url.set(file_uploaded.get_url());
-                                    props.file_uploaded.set(Some(file_uploaded));
-                                }
-                                Err(e) => props.error_message.set(format!("Error (86): {e}")),
-                            }
-                        }
-                        Err(e) => props.error_message.set(format!("Error (90): {e}")),
-                    }
-                }
-            }
+        if let Some(file_engine) = evt.files() {
+            upload_file(
+                file_engine,
+                props.file_uploaded,
+                props.files_uploaded,
+                props.object_url,
+                props.error_message,
+            )
+            .await;
         }
     };
     rsx! {
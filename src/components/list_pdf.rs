@@ -1,6 +1,9 @@
 use dioxus::prelude::*;
 
-use crate::components::ItemAction;
+use crate::{
+    components::{ItemAction, PasswordInput, Thumbnails},
+    error::Error,
+};
 
 use super::input_file::FileUploaded;
 
@@ -15,30 +18,71 @@ pub struct ListPdfProps {
     url: String,
     files_uploaded: Signal<Vec<FileUploaded>>,
     idx: usize,
+    error_message: Signal<String>,
+    #[props(default)]
+    is_select_pages: bool,
 }
 
 #[component]
-pub fn ListPdf(props: ListPdfProps) -> Element {
+pub fn ListPdf(mut props: ListPdfProps) -> Element {
+    let is_locked = props
+        .files_uploaded
+        .read()
+        .get(props.idx)
+        .map(|file_uploaded| file_uploaded.is_locked)
+        .unwrap_or(false);
+    let filename = props.filename.clone();
+    let unlock_handler = move |password: String| {
+        props.error_message.set(String::new());
+        let result = props.files_uploaded.write()[props.idx].unlock(&password);
+        match result {
+            Ok(_) => {}
+            Err(Error::IncorrectPassword) => props
+                .error_message
+                .set(format!("incorrect password for {filename}")),
+            Err(e) => props
+                .error_message
+                .set(format!("Error (list_pdf, unlock): {e}")),
+        }
+    };
+
     rsx! {
-        li { class: "flex flex-row items-center justify-between my-1",
-            a {
-                class: "w-full cursor-pointer truncate rounded-md p-1 text-xs hover:bg-neutral-200 focus:bg-neutral-200 sm:text-sm md:text-base",
-                title: props.filename,
-                href: props.url,
-                target: "iframe_pdf",
-                "{props.filename}"
-            }
+        li { class: "my-1 flex flex-col",
+            div { class: "flex flex-row items-center justify-between",
+                a {
+                    class: "w-full cursor-pointer truncate rounded-md p-1 text-xs hover:bg-neutral-200 focus:bg-neutral-200 sm:text-sm md:text-base",
+                    title: props.filename,
+                    href: props.url,
+                    target: "iframe_pdf",
+                    "{props.filename}"
+                }
+                if is_locked {
+                    PasswordInput { filename: props.filename.clone(), onunlock: unlock_handler }
+                } else if props.is_select_pages {
+                    input {
+                        class: "ml-1 w-20 rounded-md border-2 border-neutral-300 p-1 text-xs sm:w-28 sm:text-sm",
+                        r#type: "text",
+                        title: "Pages to merge, e.g. 1-3, 7",
+                        placeholder: "All pages",
+                        value: props.files_uploaded.read()[props.idx].page_range.clone(),
+                        oninput: move |evt: Event<FormData>| {
+                            props.files_uploaded.write()[props.idx].page_range = evt.value();
+                        },
+                    }
+                }
 
-            ItemAction {
-                object_url: props.url.clone(),
-                filename: props.filename.clone(),
-                download_img: DOWNLOAD_IMG,
-                trash_img: TRASH_IMG,
-                up_img: UP_IMG,
-                down_img: DOWN_IMG,
-                files_uploaded: props.files_uploaded,
-                idx: props.idx,
+                ItemAction {
+                    object_url: props.url.clone(),
+                    filename: props.filename.clone(),
+                    download_img: DOWNLOAD_IMG,
+                    trash_img: TRASH_IMG,
+                    up_img: UP_IMG,
+                    down_img: DOWN_IMG,
+                    files_uploaded: props.files_uploaded,
+                    idx: props.idx,
+                }
             }
+            Thumbnails { files_uploaded: props.files_uploaded, idx: props.idx }
         }
     }
 }
@@ -1,7 +1,11 @@
 use dioxus::prelude::*;
 use web_sys::Url;
 
-use crate::{error::Error, pdf::MergeDocument, utils::convert_vec_u8_to_pdf_blob};
+use crate::{
+    error::Error,
+    pdf::{MergeDocument, MergeInput, MergeMode, MergeOptions, OptimizeDocument},
+    utils::{convert_vec_u8_to_pdf_blob, saved_bytes_message},
+};
 
 use super::input_file::FileUploaded;
 
@@ -15,20 +19,61 @@ pub struct MergeDownloadProps {
 
 #[component]
 pub fn MergeDownload(mut props: MergeDownloadProps) -> Element {
+    let mut is_nest_outlines = use_signal(|| false);
+    let mut is_bookmark_per_input = use_signal(|| false);
+    let mut is_interleave = use_signal(|| false);
+    let mut is_reverse_second = use_signal(|| false);
+    let mut saved_message = use_signal(String::new);
     let merge_file_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        saved_message.set(String::new());
+        if let Some(file_uploaded) = props
+            .files_uploaded
+            .read()
+            .iter()
+            .find(|file_uploaded| file_uploaded.is_locked)
+        {
+            props.error_message.set(format!(
+                "{} is password protected, enter its password first",
+                file_uploaded.filename
+            ));
+            return;
+        }
+        let mode = if is_interleave() {
+            MergeMode::Interleave {
+                is_reverse_second: is_reverse_second(),
+            }
+        } else {
+            MergeMode::Append
+        };
+        let options = MergeOptions {
+            mode,
+            is_nest_outlines: is_nest_outlines(),
+            is_bookmark_per_input: is_bookmark_per_input(),
+        };
         let document = MergeDocument::merge_pdf_from_mem(
             props
                 .files_uploaded
                 .read()
                 .iter()
-                .filter_map(|file_uploaded| {
-                    let FileUploaded { file_buffer, .. } = file_uploaded;
-                    Some(file_buffer.as_slice())
+                .map(|file_uploaded| {
+                    let FileUploaded {
+                        filename,
+                        file_buffer,
+                        page_range,
+                        ..
+                    } = file_uploaded;
+                    MergeInput {
+                        page_range: Some(page_range.clone()),
+                        ..MergeInput::new(filename, file_buffer)
+                    }
                 })
                 .collect(),
+            &options,
         );
         match document {
             Ok(mut doc) => {
+                let saved_bytes = OptimizeDocument::optimize(&mut doc);
                 if !props.merge_file_object_url.read().is_empty() {
                     let _revoke_url = Url::revoke_object_url(&props.merge_file_object_url.read());
                 }
@@ -47,6 +92,7 @@ pub fn MergeDownload(mut props: MergeDownloadProps) -> Element {
                                     Ok(url) => {
                                         props.merge_file_object_url.set(url);
                                         props.merge_file_name.set("merge.pdf".to_string());
+                                        saved_message.set(saved_bytes_message(saved_bytes));
                                     }
                                     Err(e) => {
                                         props.error_message.set(format!("Error (merge, 52): {e}"));
@@ -69,6 +115,15 @@ pub fn MergeDownload(mut props: MergeDownloadProps) -> Element {
                 crate::pdf::Error::Lopdf(error) => props
                     .error_message
                     .set(format!("Error::Lopdf (merge, 71): {error}")),
+                crate::pdf::Error::InValidMergePageNumbers(filename) => props
+                    .error_message
+                    .set(format!("invalid page numbers for {filename}")),
+                crate::pdf::Error::EncryptedDocument => props
+                    .error_message
+                    .set("a PDF file is password protected".to_string()),
+                crate::pdf::Error::InterleaveNeedsTwoDocuments => props
+                    .error_message
+                    .set("interleave needs exactly two files".to_string()),
                 _ => props
                     .error_message
                     .set(format!("Error (merge, 74): {pdf_merge_e}")),
@@ -77,11 +132,53 @@ pub fn MergeDownload(mut props: MergeDownloadProps) -> Element {
     };
     rsx! {
         div { class: "mt-4 flex w-auto flex-col items-center rounded-md p-4",
-            button {
-                id: "merge",
-                class: "w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
-                onclick: merge_file_handler,
-                "Merge PDF"
+            div { class: "flex w-full flex-row items-center justify-center gap-3",
+                button {
+                    id: "merge",
+                    class: "w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                    onclick: merge_file_handler,
+                    "Merge PDF"
+                }
+                div { class: "flex flex-col text-sm sm:text-base",
+                    label { class: "flex items-center gap-2",
+                        input {
+                            id: "interleave",
+                            r#type: "checkbox",
+                            checked: is_interleave,
+                            onchange: move |evt: Event<FormData>| is_interleave.set(evt.checked()),
+                        }
+                        "Interleave pages"
+                    }
+                    if is_interleave() {
+                        label { class: "flex items-center gap-2",
+                            input {
+                                id: "reverse_second",
+                                r#type: "checkbox",
+                                checked: is_reverse_second,
+                                onchange: move |evt: Event<FormData>| is_reverse_second.set(evt.checked()),
+                            }
+                            "Reverse second file"
+                        }
+                    }
+                }
+            }
+            label { class: "mt-2 flex items-center gap-2 text-sm sm:text-base",
+                input {
+                    id: "nest_outlines",
+                    r#type: "checkbox",
+                    checked: is_nest_outlines,
+                    onchange: move |evt: Event<FormData>| is_nest_outlines.set(evt.checked()),
+                }
+                "Nest bookmarks under file names"
+            }
+            label { class: "mt-1 flex items-center gap-2 text-sm sm:text-base",
+                input {
+                    id: "bookmark_per_input",
+                    r#type: "checkbox",
+                    checked: is_bookmark_per_input,
+                    onchange: move |evt: Event<FormData>| is_bookmark_per_input.set(evt.checked()),
+                }
+                "Add a bookmark for every file"
             }
             div { class: "mt-3 flex flex-col w-full max-w-screen-sm",
                 if props.merge_file_name.read().is_empty() {
@@ -103,6 +200,11 @@ pub fn MergeDownload(mut props: MergeDownloadProps) -> Element {
                             "Download"
                         }
                     }
+                    if !saved_message().is_empty() {
+                        p { class: "mt-1 text-center text-xs text-neutral-500 sm:text-sm",
+                            {saved_message}
+                        }
+                    }
                 }
             }
         }
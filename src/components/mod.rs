@@ -31,6 +31,9 @@ pub use display_pdf::DisplayPdf;
 mod result_pdf;
 pub use result_pdf::ResultPdf;
 
+mod single_file_view;
+pub use single_file_view::SingleFileView;
+
 mod list_pdf;
 pub use list_pdf::ListPdf;
 
@@ -40,8 +43,41 @@ pub use merge_download::MergeDownload;
 mod split_action;
 pub use split_action::SplitAction;
 
+mod split_blank_pages;
+pub use split_blank_pages::SplitBlankPages;
+
 mod split_input_page_number_str;
-pub use split_input_page_number_str::SplitInputPageNumberStr;
+pub use split_input_page_number_str::{SplitInputPageNumberStr, SplitMode};
 
 mod drop_area;
 pub use drop_area::DropArea;
+
+mod password_input;
+pub use password_input::PasswordInput;
+
+mod protect_action;
+pub use protect_action::ProtectAction;
+
+mod rotate_action;
+pub use rotate_action::RotateAction;
+
+mod organize_action;
+pub use organize_action::OrganizeAction;
+
+mod page_list;
+pub use page_list::PageList;
+
+mod thumbnails;
+pub use thumbnails::Thumbnails;
+
+mod split_page_picker;
+pub use split_page_picker::SplitPagePicker;
+
+mod compress_action;
+pub use compress_action::CompressAction;
+
+mod images_to_pdf_action;
+pub use images_to_pdf_action::ImagesToPdfAction;
+
+mod pdf_to_images_action;
+pub use pdf_to_images_action::PdfToImagesAction;
@@ -22,11 +22,22 @@ pub fn Navbar() -> Element {
     let navbar_menus = vec![
         NavMenuProps::new(Route::Merge {}, "MERGE"),
         NavMenuProps::new(Route::Split {}, "SPLIT"),
+        NavMenuProps::new(Route::Organize {}, "ORGANIZE"),
+        NavMenuProps::new(Route::Rotate {}, "ROTATE"),
+        NavMenuProps::new(Route::Compress {}, "COMPRESS"),
+        NavMenuProps::new(Route::ImagesToPdf {}, "IMAGES"),
+        NavMenuProps::new(Route::Protect {}, "PROTECT"),
     ];
     let sidebar_menus = vec![
         SidebarMenuProps::new(Route::Home {}, "HOME"),
         SidebarMenuProps::new(Route::Merge {}, "MERGE PDF"),
         SidebarMenuProps::new(Route::Split {}, "SPLIT PDF"),
+        SidebarMenuProps::new(Route::Organize {}, "ORGANIZE PDF"),
+        SidebarMenuProps::new(Route::Rotate {}, "ROTATE PDF"),
+        SidebarMenuProps::new(Route::Compress {}, "COMPRESS PDF"),
+        SidebarMenuProps::new(Route::ImagesToPdf {}, "IMAGES TO PDF"),
+        SidebarMenuProps::new(Route::PdfToImages {}, "PDF TO IMAGES"),
+        SidebarMenuProps::new(Route::Protect {}, "PROTECT PDF"),
     ];
 
     rsx! {
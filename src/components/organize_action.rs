@@ -0,0 +1,150 @@
+use dioxus::prelude::*;
+use web_sys::Url;
+
+use crate::{components::PageList, pdf::OrganizeDocument, utils::save_to_object_url};
+
+use super::input_file::FileUploaded;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PageAction {
+    Remove,
+    Extract,
+    Arrange,
+}
+
+#[derive(PartialEq, Clone, Props)]
+pub struct OrganizeActionProps {
+    file_uploaded: Signal<Option<FileUploaded>>,
+    error_message: Signal<String>,
+}
+
+#[component]
+pub fn OrganizeAction(mut props: OrganizeActionProps) -> Element {
+    let page_count = use_memo(move || {
+        props
+            .file_uploaded
+            .read()
+            .as_ref()
+            .and_then(|file| OrganizeDocument::page_count_from_mem(&file.file_buffer).ok())
+            .unwrap_or(0)
+    });
+    // Every page of the uploaded file in order, reset when another file is uploaded
+    let mut page_order: Signal<Vec<u32>> = use_signal(Vec::new);
+    use_effect(move || page_order.set((1..page_count() + 1).collect()));
+    let mut page_numbers_str = use_signal(String::new);
+    let mut organize_file_name = use_signal(String::new);
+    let mut organize_file_object_url = use_signal(String::new);
+
+    let mut organize_file = move |page_action: PageAction| {
+        props.error_message.set(String::new());
+        let Some(file_uploaded) = props.file_uploaded.read().clone() else {
+            props.error_message.set("No file to organize".to_string());
+            return;
+        };
+        let document = match page_action {
+            PageAction::Remove => OrganizeDocument::remove_pages_from_mem(
+                &file_uploaded.file_buffer,
+                page_numbers_str.read().as_str(),
+            ),
+            PageAction::Extract => OrganizeDocument::extract_pages_from_mem(
+                &file_uploaded.file_buffer,
+                page_numbers_str.read().as_str(),
+            ),
+            PageAction::Arrange => OrganizeDocument::arrange_pages_from_mem(
+                &file_uploaded.file_buffer,
+                &page_order.read(),
+            ),
+        };
+        match document {
+            Ok(mut doc) => {
+                if !organize_file_object_url.read().is_empty() {
+                    let _revoke_url = Url::revoke_object_url(&organize_file_object_url.read());
+                }
+                match save_to_object_url(&mut doc) {
+                    Ok(url) => {
+                        organize_file_object_url.set(url);
+                        organize_file_name
+                            .set(format!("{}_organized.pdf", file_uploaded.get_filename()));
+                    }
+                    Err(e) => props
+                        .error_message
+                        .set(format!("Error (organize, save): {e}")),
+                }
+            }
+            Err(pdf_organize_e) => match pdf_organize_e {
+                crate::pdf::Error::Lopdf(error) => props
+                    .error_message
+                    .set(format!("Error::Lopdf (organize, organize): {error}")),
+                crate::pdf::Error::SplitPagesStrIsEmpty => props
+                    .error_message
+                    .set("enter the pages to remove or extract".to_string()),
+                crate::pdf::Error::InValidPageNumbers
+                | crate::pdf::Error::PageNumberOverFlow
+                | crate::pdf::Error::CannotCreateRegex => {
+                    props.error_message.set("invalid page numbers".to_string())
+                }
+                crate::pdf::Error::CannotRemoveAllPages => props
+                    .error_message
+                    .set("cannot remove every page".to_string()),
+                crate::pdf::Error::EncryptedDocument => props
+                    .error_message
+                    .set("the PDF file is password protected".to_string()),
+                _ => props
+                    .error_message
+                    .set(format!("Error (organize, organize): {pdf_organize_e}")),
+            },
+        }
+    };
+    rsx! {
+        div { class: "mt-4 flex w-full flex-col items-center rounded-md p-4",
+            div { class: "flex w-full max-w-screen-sm flex-row items-center justify-center gap-2 text-sm sm:text-base",
+                input {
+                    id: "organize_pages",
+                    class: "w-full rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "text",
+                    title: "Pages, e.g. 3, 7-9",
+                    placeholder: "Example: 3, 7-9",
+                    value: page_numbers_str,
+                    oninput: move |evt: Event<FormData>| page_numbers_str.set(evt.value()),
+                }
+                button {
+                    class: "text-nowrap rounded-md bg-neutral-200 p-2 hover:bg-neutral-400 focus:bg-neutral-400",
+                    onclick: move |_evt| organize_file(PageAction::Remove),
+                    "Remove pages"
+                }
+                button {
+                    class: "text-nowrap rounded-md bg-neutral-200 p-2 hover:bg-neutral-400 focus:bg-neutral-400",
+                    onclick: move |_evt| organize_file(PageAction::Extract),
+                    "Extract pages"
+                }
+            }
+            PageList { page_order }
+            button {
+                id: "arrange",
+                class: "mt-2 w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                onclick: move |_evt| organize_file(PageAction::Arrange),
+                "Apply page order"
+            }
+            div { class: "mt-3 flex flex-col w-full max-w-screen-sm",
+                if !organize_file_name.read().is_empty() {
+                    label { class: "text-sm pl-2", r#for: "organize_filename", "Filename" }
+                    div { class: "flex justify-between gap-2",
+                        input {
+                            id: "organize_filename",
+                            class: "text-base rounded-md border-2 w-full border-neutral-100 p-2",
+                            r#type: "text",
+                            value: organize_file_name,
+                            oninput: move |evt: Event<FormData>| organize_file_name.set(evt.value()),
+                        }
+                        a {
+                            class: "rounded-md p-2 bg-sky-200 hover:bg-sky-400 focus:bg-sky-400",
+                            href: organize_file_object_url,
+                            download: organize_file_name,
+                            "Download"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use dioxus::prelude::*;
+
+const TRASH_IMG: Asset = asset!("/assets/imgs/trash-svgrepo-com.svg",);
+const UP_IMG: Asset = asset!("/assets/imgs/up-svgrepo-com.svg",);
+const DOWN_IMG: Asset = asset!("/assets/imgs/down-svgrepo-com.svg",);
+const DUPLICATE_IMG: Asset = asset!("/assets/imgs/duplicate.svg",);
+
+#[derive(PartialEq, Clone, Props)]
+pub struct PageListProps {
+    // Page numbers of the original document, in the new order
+    page_order: Signal<Vec<u32>>,
+}
+
+#[component]
+pub fn PageList(mut props: PageListProps) -> Element {
+    rsx! {
+        ul { class: "mt-2 flex h-auto max-h-64 w-full max-w-screen-sm flex-col overflow-auto rounded-md border-2 border-neutral-300",
+            for (idx , page_number) in props.page_order.read().iter().enumerate() {
+                li {
+                    key: "{idx}-{page_number}",
+                    class: "my-1 flex flex-row items-center justify-between px-2 text-xs sm:text-sm md:text-base",
+                    span { "Page {page_number}" }
+                    div { class: "flex flex-row justify-end",
+                        img {
+                            class: "w-6 cursor-pointer rounded-md p-1 hover:bg-neutral-200 focus:bg-neutral-200 sm:w-7 md:w-8",
+                            src: UP_IMG,
+                            alt: "Up",
+                            onclick: move |_evt| {
+                                if idx > 0 {
+                                    props.page_order.write().swap(idx, idx - 1);
+                                }
+                            },
+                        }
+                        img {
+                            class: "w-6 cursor-pointer rounded-md p-1 hover:bg-neutral-200 focus:bg-neutral-200 sm:w-7 md:w-8",
+                            src: DOWN_IMG,
+                            alt: "Down",
+                            onclick: move |_evt| {
+                                if idx + 1 < props.page_order.read().len() {
+                                    props.page_order.write().swap(idx, idx + 1);
+                                }
+                            },
+                        }
+                        img {
+                            class: "w-6 cursor-pointer rounded-md p-1 hover:bg-neutral-200 focus:bg-neutral-200 sm:w-7 md:w-8",
+                            src: DUPLICATE_IMG,
+                            alt: "Duplicate",
+                            onclick: move |_evt| {
+                                let page_number = props.page_order.read()[idx];
+                                props.page_order.write().insert(idx + 1, page_number);
+                            },
+                        }
+                        img {
+                            class: "w-6 cursor-pointer rounded-md p-1 hover:bg-neutral-200 focus:bg-neutral-200 sm:w-7 md:w-8",
+                            src: TRASH_IMG,
+                            alt: "Delete",
+                            onclick: move |_evt| {
+                                props.page_order.write().remove(idx);
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use dioxus::prelude::*;
+
+#[derive(PartialEq, Clone, Props)]
+pub struct PasswordInputProps {
+    #[props(into)]
+    filename: String,
+    onunlock: EventHandler<String>,
+}
+
+#[component]
+pub fn PasswordInput(props: PasswordInputProps) -> Element {
+    let mut password = use_signal(String::new);
+
+    rsx! {
+        div { class: "flex items-center gap-1",
+            input {
+                class: "w-24 rounded-md border-2 border-neutral-300 p-1 text-xs sm:w-32 sm:text-sm",
+                r#type: "password",
+                placeholder: "Password",
+                title: "Password of {props.filename}",
+                value: password,
+                oninput: move |evt: Event<FormData>| password.set(evt.value()),
+                onkeydown: move |evt: Event<KeyboardData>| {
+                    if evt.key() == Key::Enter {
+                        props.onunlock.call(password());
+                    }
+                },
+            }
+            button {
+                class: "rounded-md bg-neutral-200 p-1 text-xs hover:bg-neutral-400 focus:bg-neutral-400 sm:text-sm",
+                onclick: move |_evt| props.onunlock.call(password()),
+                "Unlock"
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use dioxus::prelude::*;
+
+use crate::{
+    pdf::{
+        OrganizeDocument, RenderImageFormat, RenderImageOptions, SplitDocumnet, ThumbnailDocument,
+    },
+    utils::{convert_vec_u8_to_jpeg_blob, convert_vec_u8_to_png_blob},
+};
+
+use super::input_file::FileUploaded;
+
+#[derive(PartialEq, Clone, Props)]
+pub struct PdfToImagesActionProps {
+    file_uploaded: Signal<Option<FileUploaded>>,
+    files_uploaded: Signal<Vec<FileUploaded>>,
+    error_message: Signal<String>,
+}
+
+#[component]
+pub fn PdfToImagesAction(mut props: PdfToImagesActionProps) -> Element {
+    let default_options = RenderImageOptions::default();
+    let mut range_str = use_signal(String::new);
+    let mut dpi_str = use_signal(|| default_options.dpi.to_string());
+    let mut format = use_signal(|| default_options.format);
+    let mut jpeg_quality_str = use_signal(|| default_options.jpeg_quality.to_string());
+
+    let convert_file_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        let Some(file_uploaded) = props.file_uploaded.read().clone() else {
+            props.error_message.set("No file to convert".to_string());
+            return;
+        };
+        let options = match (
+            dpi_str.read().trim().parse::<u32>(),
+            jpeg_quality_str.read().trim().parse::<u8>(),
+        ) {
+            (Ok(dpi), Ok(jpeg_quality)) => RenderImageOptions {
+                dpi,
+                format: format(),
+                jpeg_quality,
+            },
+            _ => {
+                props
+                    .error_message
+                    .set("DPI and quality must be numbers".to_string());
+                return;
+            }
+        };
+        let page_numbers = OrganizeDocument::page_count_from_mem(&file_uploaded.file_buffer)
+            .and_then(|max_pages| {
+                if range_str.read().trim().is_empty() {
+                    Ok((1..max_pages + 1).collect())
+                } else {
+                    SplitDocumnet::page_numbers_from_str(range_str.read().as_str(), max_pages)
+                }
+            });
+        let images = page_numbers.and_then(|page_numbers| {
+            ThumbnailDocument::render_images_from_mem(
+                &file_uploaded.file_buffer,
+                &page_numbers,
+                &options,
+            )
+            .map(|images| page_numbers.into_iter().zip(images).collect::<Vec<_>>())
+        });
+        match images {
+            Ok(images) => {
+                for old_file in props.files_uploaded.read().iter() {
+                    old_file.revoke_object_url();
+                }
+                props.files_uploaded.write().clear();
+                let extension = match options.format {
+                    RenderImageFormat::Png => "png",
+                    RenderImageFormat::Jpeg => "jpg",
+                };
+                for (page_number, image) in images {
+                    let blob = match options.format {
+                        RenderImageFormat::Png => convert_vec_u8_to_png_blob(&image),
+                        RenderImageFormat::Jpeg => convert_vec_u8_to_jpeg_blob(&image),
+                    };
+                    let filename = format!(
+                        "{}_page{page_number}.{extension}",
+                        file_uploaded.get_filename()
+                    );
+                    match blob.and_then(|blob| FileUploaded::from_result(filename, blob, image)) {
+                        Ok(file) => props.files_uploaded.write().push(file),
+                        Err(e) => {
+                            props
+                                .error_message
+                                .set(format!("Error (pdf_to_images, result): {e}"));
+                            return;
+                        }
+                    }
+                }
+            }
+            Err(pdf_render_e) => match pdf_render_e {
+                crate::pdf::Error::InValidPageRange(e) => props.error_message.set(e.to_string()),
+                crate::pdf::Error::SplitPagesStrIsEmpty => {
+                    props.error_message.set("invalid page numbers".to_string())
+                }
+                crate::pdf::Error::EncryptedDocument => props.error_message.set(format!(
+                    "{} is password protected",
+                    file_uploaded.get_filename()
+                )),
+                crate::pdf::Error::InValidRenderOptions => props
+                    .error_message
+                    .set("DPI must be 1 to 600 and quality 1 to 100".to_string()),
+                crate::pdf::Error::CannotRenderDocument => props
+                    .error_message
+                    .set("the PDF file could not be rendered".to_string()),
+                _ => props
+                    .error_message
+                    .set(format!("Error (pdf_to_images, render): {pdf_render_e}")),
+            },
+        }
+    };
+    rsx! {
+        div { class: "mt-4 flex w-4/5 max-w-screen-md flex-col items-center rounded-md p-4",
+            div { class: "flex w-full max-w-screen-sm flex-row flex-wrap items-center justify-center gap-2 text-sm sm:text-base",
+                input {
+                    id: "images_range",
+                    class: "w-full rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "text",
+                    title: "Pages to convert, e.g. 1-3, 7, odd",
+                    placeholder: "All pages",
+                    value: range_str,
+                    oninput: move |evt: Event<FormData>| range_str.set(evt.value()),
+                }
+                label { r#for: "images_dpi", "DPI: " }
+                input {
+                    id: "images_dpi",
+                    class: "w-20 rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "number",
+                    min: "1",
+                    max: "600",
+                    value: dpi_str,
+                    oninput: move |evt: Event<FormData>| dpi_str.set(evt.value()),
+                }
+                label { r#for: "images_format", "Format: " }
+                select {
+                    id: "images_format",
+                    class: "rounded-md border-2 border-neutral-100 p-2",
+                    onchange: move |evt: Event<FormData>| {
+                        format
+                            .set(
+                                match evt.value().as_str() {
+                                    "jpeg" => RenderImageFormat::Jpeg,
+                                    _ => RenderImageFormat::Png,
+                                },
+                            )
+                    },
+                    option { value: "png", selected: format() == RenderImageFormat::Png, "PNG" }
+                    option {
+                        value: "jpeg",
+                        selected: format() == RenderImageFormat::Jpeg,
+                        "JPEG"
+                    }
+                }
+                if format() == RenderImageFormat::Jpeg {
+                    label { r#for: "images_quality", "Quality: " }
+                    input {
+                        id: "images_quality",
+                        class: "w-20 rounded-md border-2 border-neutral-100 p-2 text-base",
+                        r#type: "number",
+                        min: "1",
+                        max: "100",
+                        value: jpeg_quality_str,
+                        oninput: move |evt: Event<FormData>| jpeg_quality_str.set(evt.value()),
+                    }
+                }
+            }
+            button {
+                id: "pdf_to_images",
+                class: "mt-2 w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                onclick: convert_file_handler,
+                "Convert to images"
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use dioxus::prelude::*;
+use web_sys::Url;
+
+use crate::{
+    pdf::{EncryptDocument, Permissions},
+    utils::save_to_object_url,
+};
+
+use super::input_file::FileUploaded;
+
+#[derive(PartialEq, Clone, Props)]
+pub struct ProtectActionProps {
+    file_uploaded: Signal<Option<FileUploaded>>,
+    error_message: Signal<String>,
+}
+
+#[component]
+pub fn ProtectAction(mut props: ProtectActionProps) -> Element {
+    let mut user_password = use_signal(String::new);
+    let mut owner_password = use_signal(String::new);
+    let mut is_print = use_signal(|| true);
+    let mut is_copy = use_signal(|| true);
+    let mut is_modify = use_signal(|| true);
+    let mut protect_file_name = use_signal(String::new);
+    let mut protect_file_object_url = use_signal(String::new);
+
+    let protect_file_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        let Some(file_uploaded) = props.file_uploaded.read().clone() else {
+            props.error_message.set("No file to protect".to_string());
+            return;
+        };
+        let permissions = Permissions {
+            is_print: is_print(),
+            is_copy: is_copy(),
+            is_modify: is_modify(),
+        };
+        let document = EncryptDocument::encrypt_pdf_from_mem(
+            &file_uploaded.file_buffer,
+            &user_password.read(),
+            &owner_password.read(),
+            permissions,
+        );
+        match document {
+            Ok(mut doc) => {
+                if !protect_file_object_url.read().is_empty() {
+                    let _revoke_url = Url::revoke_object_url(&protect_file_object_url.read());
+                }
+                match save_to_object_url(&mut doc) {
+                    Ok(url) => {
+                        protect_file_object_url.set(url);
+                        protect_file_name
+                            .set(format!("{}_protected.pdf", file_uploaded.get_filename()));
+                    }
+                    Err(e) => props
+                        .error_message
+                        .set(format!("Error (protect, save): {e}")),
+                }
+            }
+            Err(pdf_encrypt_e) => match pdf_encrypt_e {
+                crate::pdf::Error::Lopdf(error) => props
+                    .error_message
+                    .set(format!("Error::Lopdf (protect, encrypt): {error}")),
+                crate::pdf::Error::PasswordIsEmpty => props
+                    .error_message
+                    .set("enter a user or owner password".to_string()),
+                crate::pdf::Error::EncryptedDocument => props
+                    .error_message
+                    .set("the PDF file is password protected".to_string()),
+                _ => props
+                    .error_message
+                    .set(format!("Error (protect, encrypt): {pdf_encrypt_e}")),
+            },
+        }
+    };
+    rsx! {
+        div { class: "mt-4 flex w-full flex-col items-center rounded-md p-4",
+            div { class: "flex w-full max-w-screen-sm flex-col gap-2 text-sm sm:text-base",
+                label { class: "pl-2 text-sm", r#for: "user_password", "Password to open" }
+                input {
+                    id: "user_password",
+                    class: "rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "password",
+                    value: user_password,
+                    oninput: move |evt: Event<FormData>| user_password.set(evt.value()),
+                }
+                label { class: "pl-2 text-sm", r#for: "owner_password", "Password to change permissions" }
+                input {
+                    id: "owner_password",
+                    class: "rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "password",
+                    value: owner_password,
+                    oninput: move |evt: Event<FormData>| owner_password.set(evt.value()),
+                }
+                div { class: "flex flex-row flex-wrap justify-center gap-3",
+                    label { class: "flex items-center gap-2",
+                        input {
+                            id: "allow_print",
+                            r#type: "checkbox",
+                            checked: is_print,
+                            onchange: move |evt: Event<FormData>| is_print.set(evt.checked()),
+                        }
+                        "Allow printing"
+                    }
+                    label { class: "flex items-center gap-2",
+                        input {
+                            id: "allow_copy",
+                            r#type: "checkbox",
+                            checked: is_copy,
+                            onchange: move |evt: Event<FormData>| is_copy.set(evt.checked()),
+                        }
+                        "Allow copying"
+                    }
+                    label { class: "flex items-center gap-2",
+                        input {
+                            id: "allow_modify",
+                            r#type: "checkbox",
+                            checked: is_modify,
+                            onchange: move |evt: Event<FormData>| is_modify.set(evt.checked()),
+                        }
+                        "Allow modifying"
+                    }
+                }
+                button {
+                    id: "protect",
+                    class: "rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                    onclick: protect_file_handler,
+                    "Protect PDF"
+                }
+            }
+            div { class: "mt-3 flex flex-col w-full max-w-screen-sm",
+                if !protect_file_name.read().is_empty() {
+                    label { class: "text-sm pl-2", r#for: "protect_filename", "Filename" }
+                    div { class: "flex justify-between gap-2",
+                        input {
+                            id: "protect_filename",
+                            class: "text-base rounded-md border-2 w-full border-neutral-100 p-2",
+                            r#type: "text",
+                            value: protect_file_name,
+                            oninput: move |evt: Event<FormData>| protect_file_name.set(evt.value()),
+                        }
+                        a {
+                            class: "rounded-md p-2 bg-sky-200 hover:bg-sky-400 focus:bg-sky-400",
+                            href: protect_file_object_url,
+                            download: protect_file_name,
+                            "Download"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
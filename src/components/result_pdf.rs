@@ -1,6 +1,11 @@
 use dioxus::prelude::*;
+use web_sys::Url;
 
-use crate::components::{DisplayPdf, ListPdf};
+use crate::{
+    components::{DisplayPdf, ListPdf},
+    error::Error,
+    utils::{convert_vec_u8_to_zip_blob, create_zip},
+};
 
 use super::input_file::FileUploaded;
 
@@ -11,10 +16,50 @@ pub struct ResultPdfProps {
     files_uploaded: Signal<Vec<FileUploaded>>,
     error_message: Signal<String>,
     download_element: Option<Element>,
+    #[props(default)]
+    is_select_pages: bool,
+    // Offer every listed file in one ZIP archive with this name
+    zip_file_name: Option<String>,
 }
 
 #[component]
-pub fn ResultPdf(props: ResultPdfProps) -> Element {
+pub fn ResultPdf(mut props: ResultPdfProps) -> Element {
+    let mut zip_file_object_url = use_signal(String::new);
+    let mut revoke_zip_file = move || {
+        if !zip_file_object_url.read().is_empty() {
+            let _revoke_url = Url::revoke_object_url(&zip_file_object_url.read());
+        }
+        zip_file_object_url.set(String::new());
+    };
+    // The archive only holds the files listed when it was packed
+    use_effect(move || {
+        props.files_uploaded.read();
+        revoke_zip_file();
+    });
+
+    let zip_file_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        revoke_zip_file();
+        let files_uploaded = props.files_uploaded.read();
+        let files: Vec<(&str, &[u8])> = files_uploaded
+            .iter()
+            .map(|file| (file.filename.as_str(), file.file_buffer.as_slice()))
+            .collect();
+        let result_url = create_zip(&files)
+            .and_then(|zip| convert_vec_u8_to_zip_blob(&zip))
+            .and_then(|blob| {
+                Url::create_object_url_with_blob(&blob).map_err(|js_value| {
+                    Error::JsValue(format!("Error (result_pdf, object_url): {js_value:?}"))
+                })
+            });
+        match result_url {
+            Ok(url) => zip_file_object_url.set(url),
+            Err(e) => props
+                .error_message
+                .set(format!("Error (result_pdf, zip): {e}")),
+        }
+    };
+
     rsx! {
         section { class: "mt-4 flex h-full w-4/5 max-w-screen-md flex-row justify-between overflow-auto rounded-md xl:w-[95%] xl:max-w-full",
             div { class: "w-full h-4/5 xl:w-1/2",
@@ -29,6 +74,29 @@ pub fn ResultPdf(props: ResultPdfProps) -> Element {
                                         url,
                                         files_uploaded: props.files_uploaded,
                                         idx,
+                                        error_message: props.error_message,
+                                        is_select_pages: props.is_select_pages,
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if let Some(zip_file_name) = props.zip_file_name.clone() {
+                        if props.files_uploaded.read().len() > 1 {
+                            div { class: "mt-2 flex flex-row items-center justify-center gap-2 text-sm sm:text-base",
+                                if zip_file_object_url.read().is_empty() {
+                                    button {
+                                        id: "download_all",
+                                        class: "rounded-md bg-neutral-200 p-2 hover:bg-neutral-400 focus:bg-neutral-400",
+                                        onclick: zip_file_handler,
+                                        "Download all"
+                                    }
+                                } else {
+                                    a {
+                                        class: "rounded-md p-2 bg-sky-200 hover:bg-sky-400 focus:bg-sky-400",
+                                        href: zip_file_object_url,
+                                        download: "{zip_file_name}",
+                                        "Download {zip_file_name}"
                                     }
                                 }
                             }
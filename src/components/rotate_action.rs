@@ -0,0 +1,186 @@
+use dioxus::prelude::*;
+use web_sys::Url;
+
+use crate::{
+    pdf::{RotateDocument, SplitDocumnet},
+    utils::save_to_object_url,
+};
+
+use super::input_file::FileUploaded;
+
+#[derive(PartialEq, Clone, Props)]
+pub struct RotateActionProps {
+    file_uploaded: Signal<Option<FileUploaded>>,
+    error_message: Signal<String>,
+}
+
+#[component]
+pub fn RotateAction(mut props: RotateActionProps) -> Element {
+    // Current rotation of every page of the uploaded file
+    let page_rotations = use_memo(move || {
+        props
+            .file_uploaded
+            .read()
+            .as_ref()
+            .and_then(|file| RotateDocument::page_rotations_from_mem(&file.file_buffer).ok())
+            .unwrap_or_default()
+    });
+    // Clockwise angle added to every page, reset when another file is uploaded
+    let mut added_rotations: Signal<Vec<i64>> = use_signal(Vec::new);
+    use_effect(move || added_rotations.set(vec![0; page_rotations.read().len()]));
+    let mut range_str = use_signal(String::new);
+    let mut rotate_file_name = use_signal(String::new);
+    let mut rotate_file_object_url = use_signal(String::new);
+
+    let mut rotate_range = move |angle: i64| {
+        props.error_message.set(String::new());
+        let max_pages = added_rotations.read().len() as u32;
+        let page_numbers = if range_str.read().trim().is_empty() {
+            Ok((1..max_pages + 1).collect())
+        } else {
+            SplitDocumnet::page_numbers_from_str(range_str.read().as_str(), max_pages)
+        };
+        match page_numbers {
+            Ok(page_numbers) => {
+                let mut added_rotations = added_rotations.write();
+                for page_number in page_numbers {
+                    if let Some(rotation) = added_rotations.get_mut(page_number as usize - 1) {
+                        *rotation += angle;
+                    }
+                }
+            }
+            Err(_) => props.error_message.set("invalid page numbers".to_string()),
+        }
+    };
+
+    let rotate_file_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        let Some(file_uploaded) = props.file_uploaded.read().clone() else {
+            props.error_message.set("No file to rotate".to_string());
+            return;
+        };
+        let rotations: Vec<(u32, i64)> = added_rotations
+            .read()
+            .iter()
+            .enumerate()
+            .filter(|(_, angle)| *angle % 360 != 0)
+            .map(|(idx, angle)| (idx as u32 + 1, *angle))
+            .collect();
+        let document = RotateDocument::rotate_pdf_from_mem(&file_uploaded.file_buffer, &rotations);
+        match document {
+            Ok(mut doc) => {
+                if !rotate_file_object_url.read().is_empty() {
+                    let _revoke_url = Url::revoke_object_url(&rotate_file_object_url.read());
+                }
+                match save_to_object_url(&mut doc) {
+                    Ok(url) => {
+                        rotate_file_object_url.set(url);
+                        rotate_file_name
+                            .set(format!("{}_rotated.pdf", file_uploaded.get_filename()));
+                    }
+                    Err(e) => props
+                        .error_message
+                        .set(format!("Error (rotate, save): {e}")),
+                }
+            }
+            Err(pdf_rotate_e) => match pdf_rotate_e {
+                crate::pdf::Error::Lopdf(error) => props
+                    .error_message
+                    .set(format!("Error::Lopdf (rotate, rotate): {error}")),
+                crate::pdf::Error::EncryptedDocument => props
+                    .error_message
+                    .set("the PDF file is password protected".to_string()),
+                _ => props
+                    .error_message
+                    .set(format!("Error (rotate, rotate): {pdf_rotate_e}")),
+            },
+        }
+    };
+    rsx! {
+        div { class: "mt-4 flex w-full flex-col items-center rounded-md p-4",
+            div { class: "flex w-full max-w-screen-sm flex-row items-center justify-center gap-2 text-sm sm:text-base",
+                input {
+                    id: "rotate_range",
+                    class: "w-full rounded-md border-2 border-neutral-100 p-2 text-base",
+                    r#type: "text",
+                    title: "Pages to rotate, e.g. 1-3, 7",
+                    placeholder: "All pages",
+                    value: range_str,
+                    oninput: move |evt: Event<FormData>| range_str.set(evt.value()),
+                }
+                button {
+                    class: "text-nowrap rounded-md bg-neutral-200 p-2 hover:bg-neutral-400 focus:bg-neutral-400",
+                    onclick: move |_evt| rotate_range(-90),
+                    "Rotate left"
+                }
+                button {
+                    class: "text-nowrap rounded-md bg-neutral-200 p-2 hover:bg-neutral-400 focus:bg-neutral-400",
+                    onclick: move |_evt| rotate_range(90),
+                    "Rotate right"
+                }
+            }
+            ul { class: "mt-2 flex h-auto max-h-64 w-full max-w-screen-sm flex-col overflow-auto rounded-md border-2 border-neutral-300",
+                for (idx , rotation) in page_rotations.read().iter().enumerate() {
+                    {
+                        let angle = added_rotations.read().get(idx).copied().unwrap_or(0);
+                        let rotation = (rotation + angle).rem_euclid(360);
+                        rsx! {
+                            li { class: "my-1 flex flex-row items-center justify-between px-2 text-xs sm:text-sm md:text-base",
+                                span { "Page {idx + 1}" }
+                                div { class: "flex flex-row items-center gap-2",
+                                    span { "{rotation}°" }
+                                    button {
+                                        class: "rounded-md bg-neutral-200 px-2 hover:bg-neutral-400 focus:bg-neutral-400",
+                                        title: "Rotate page {idx + 1} left",
+                                        onclick: move |_evt| {
+                                            if let Some(angle) = added_rotations.write().get_mut(idx) {
+                                                *angle -= 90;
+                                            }
+                                        },
+                                        "↺"
+                                    }
+                                    button {
+                                        class: "rounded-md bg-neutral-200 px-2 hover:bg-neutral-400 focus:bg-neutral-400",
+                                        title: "Rotate page {idx + 1} right",
+                                        onclick: move |_evt| {
+                                            if let Some(angle) = added_rotations.write().get_mut(idx) {
+                                                *angle += 90;
+                                            }
+                                        },
+                                        "↻"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            button {
+                id: "rotate",
+                class: "mt-2 w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                onclick: rotate_file_handler,
+                "Rotate PDF"
+            }
+            div { class: "mt-3 flex flex-col w-full max-w-screen-sm",
+                if !rotate_file_name.read().is_empty() {
+                    label { class: "text-sm pl-2", r#for: "rotate_filename", "Filename" }
+                    div { class: "flex justify-between gap-2",
+                        input {
+                            id: "rotate_filename",
+                            class: "text-base rounded-md border-2 w-full border-neutral-100 p-2",
+                            r#type: "text",
+                            value: rotate_file_name,
+                            oninput: move |evt: Event<FormData>| rotate_file_name.set(evt.value()),
+                        }
+                        a {
+                            class: "rounded-md p-2 bg-sky-200 hover:bg-sky-400 focus:bg-sky-400",
+                            href: rotate_file_object_url,
+                            download: rotate_file_name,
+                            "Download"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
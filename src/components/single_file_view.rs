@@ -0,0 +1,161 @@
+use dioxus::prelude::*;
+use dioxus_elements::HasFileData;
+
+use crate::{
+    components::{
+        input_file::{
+            split_input_file::{upload_file, SplitInputFile},
+            FileUploaded,
+        },
+        DisplayPdf, DropArea, Hero, InputFile, PasswordInput, ResultPdf,
+    },
+    error::Error,
+};
+
+#[derive(PartialEq, Clone, Props)]
+pub struct SingleFileViewProps {
+    title: String,
+    id: String,
+    input_name: String,
+    file_uploaded: Signal<Option<FileUploaded>>,
+    files_uploaded: Signal<Vec<FileUploaded>>,
+    object_url: Signal<String>,
+    error_message: Signal<String>,
+    action_element: Element,
+    // The results are listed below the action and offered in one ZIP named "{filename}_{suffix}.zip",
+    // without it the action is shown next to the preview of the uploaded file
+    results_zip_suffix: Option<String>,
+}
+
+// A tool working on one uploaded PDF file: upload, drag and drop, unlock, then the action
+#[component]
+pub fn SingleFileView(mut props: SingleFileViewProps) -> Element {
+    let is_display = use_memo(move || props.file_uploaded.read().is_some());
+    let is_locked = use_memo(move || {
+        props
+            .file_uploaded
+            .read()
+            .as_ref()
+            .map(|file| file.is_locked)
+            .unwrap_or(false)
+    });
+    let mut is_drag = use_signal(|| false);
+
+    let id = props.id.clone();
+    let unlock_handler = move |password: String| {
+        props.error_message.set(String::new());
+        let result = match props.file_uploaded.write().as_mut() {
+            Some(file) => file.unlock(&password).map(|_| file.get_url()),
+            None => return,
+        };
+        match result {
+            Ok(url) => props.object_url.set(url),
+            Err(Error::IncorrectPassword) => {
+                props.error_message.set("incorrect password".to_string())
+            }
+            Err(e) => props
+                .error_message
+                .set(format!("Error ({id}, unlock): {e}")),
+        }
+    };
+
+    let drag_upload_file_handler = move |evt: Event<DragData>| {
+        evt.prevent_default();
+        is_drag.set(false);
+        async move {
+            if let Some(file_engine) = evt.files() {
+                upload_file(
+                    file_engine,
+                    props.file_uploaded,
+                    props.files_uploaded,
+                    props.object_url,
+                    props.error_message,
+                )
+                .await;
+            }
+        }
+    };
+
+    let filename = props
+        .file_uploaded
+        .read()
+        .as_ref()
+        .map(|file| file.filename.clone())
+        .unwrap_or_default();
+    let zip_file_name = props.results_zip_suffix.as_ref().and_then(|suffix| {
+        props
+            .file_uploaded
+            .read()
+            .as_ref()
+            .map(|file| format!("{}_{suffix}.zip", file.get_filename()))
+    });
+    let file_uploaded = props.file_uploaded;
+    let files_uploaded = props.files_uploaded;
+    let object_url = props.object_url;
+    let error_message = props.error_message;
+
+    rsx! {
+        Hero { title: props.title }
+        main {
+            id: props.id,
+            class: "flex h-screen w-full flex-col items-center",
+            ondragenter: move |evt| {
+                evt.prevent_default();
+                is_drag.set(true);
+            },
+            ondragover: move |evt| {
+                evt.prevent_default();
+                is_drag.set(true);
+            },
+            ondragleave: move |evt| {
+                evt.prevent_default();
+                is_drag.set(false);
+            },
+            ondrop: drag_upload_file_handler,
+            InputFile {
+                name: props.input_name,
+                file_input_element: rsx! {
+                    SplitInputFile {
+                        file_uploaded,
+                        files_uploaded,
+                        object_url,
+                        error_message,
+                    }
+                },
+            }
+            if !error_message().is_empty() {
+                p { class: "text-center text-red-500", {error_message} }
+            }
+            if is_locked() {
+                section { class: "mt-2 flex flex-col items-center w-full",
+                    p { class: "mb-1 text-sm sm:text-base", "This PDF is password protected" }
+                    PasswordInput { filename, onunlock: unlock_handler }
+                }
+            } else if is_display() {
+                if props.results_zip_suffix.is_some() {
+                    {props.action_element}
+                } else {
+                    section { class: "mt-4 flex h-full w-4/5 max-w-screen-md flex-row justify-between overflow-auto rounded-md xl:w-[95%] xl:max-w-full",
+                        div { class: "w-full h-4/5 xl:w-1/2",
+                            p { class: "text-center text-sm sm:text-base", {filename} }
+                            {props.action_element}
+                        }
+                        DisplayPdf { is_display, object_url }
+                    }
+                }
+            }
+            if props.results_zip_suffix.is_some() {
+                ResultPdf {
+                    is_display,
+                    object_url,
+                    files_uploaded,
+                    error_message,
+                    zip_file_name,
+                }
+            }
+            if is_drag() {
+                DropArea { is_drag }
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use dioxus::prelude::*;
 
-use crate::components::SplitInputPageNumberStr;
+use crate::components::{SplitBlankPages, SplitInputPageNumberStr, SplitMode, SplitPagePicker};
 
 use super::input_file::FileUploaded;
 
@@ -16,28 +16,93 @@ pub struct SplitActionProps {
 pub fn SplitAction(props: SplitActionProps) -> Element {
     let split_page_numbers_str = use_signal(String::new);
     let split_fixed_page_numbers_str = use_signal(String::new);
+    let split_outline_level_str = use_signal(String::new);
+    let split_size_limit_str = use_signal(String::new);
+    let split_text_pattern_str = use_signal(String::new);
+    let mut is_pick_pages = use_signal(|| false);
+    let saved_message = use_signal(String::new);
 
     rsx! {
         section { class: "mt-2 flex flex-col items-center w-full",
             SplitInputPageNumberStr {
                 name: "Select pages: ",
-                example: "Example: 1, 2-3, 5",
+                example: "Example: 1, 2-3, 5-last !7, odd",
                 signal_page_numbers_str: split_page_numbers_str,
-                is_fiexed_page: false,
+                split_mode: SplitMode::PageNumbers,
                 error_message: props.error_message,
                 file_uploaded: props.file_uploaded,
                 files_uploaded: props.files_uploaded,
                 object_url: props.object_url,
+                saved_message,
+            }
+            label { class: "flex items-center gap-2 text-sm sm:text-base",
+                input {
+                    id: "pick_pages",
+                    r#type: "checkbox",
+                    checked: is_pick_pages,
+                    onchange: move |evt: Event<FormData>| is_pick_pages.set(evt.checked()),
+                }
+                "Pick pages from thumbnails"
+            }
+            if is_pick_pages() {
+                SplitPagePicker {
+                    file_uploaded: props.file_uploaded,
+                    signal_page_numbers_str: split_page_numbers_str,
+                }
             }
             SplitInputPageNumberStr {
                 name: "Fixed ranges: ",
                 example: "Example: 2",
                 signal_page_numbers_str: split_fixed_page_numbers_str,
-                is_fiexed_page: true,
+                split_mode: SplitMode::FixedPages,
+                error_message: props.error_message,
+                file_uploaded: props.file_uploaded,
+                files_uploaded: props.files_uploaded,
+                object_url: props.object_url,
+                saved_message,
+            }
+            SplitBlankPages {
+                error_message: props.error_message,
+                file_uploaded: props.file_uploaded,
+                files_uploaded: props.files_uploaded,
+                object_url: props.object_url,
+                saved_message,
+            }
+            SplitInputPageNumberStr {
+                name: "Max size (MB): ",
+                example: "Example: 10",
+                signal_page_numbers_str: split_size_limit_str,
+                split_mode: SplitMode::SizeLimit,
                 error_message: props.error_message,
                 file_uploaded: props.file_uploaded,
                 files_uploaded: props.files_uploaded,
                 object_url: props.object_url,
+                saved_message,
+            }
+            SplitInputPageNumberStr {
+                name: "Text pattern: ",
+                example: r"Example: Invoice No\. (\d+)",
+                signal_page_numbers_str: split_text_pattern_str,
+                split_mode: SplitMode::TextPattern,
+                error_message: props.error_message,
+                file_uploaded: props.file_uploaded,
+                files_uploaded: props.files_uploaded,
+                object_url: props.object_url,
+                saved_message,
+            }
+            SplitInputPageNumberStr {
+                name: "Bookmark level: ",
+                example: "Example: 1",
+                signal_page_numbers_str: split_outline_level_str,
+                split_mode: SplitMode::OutlineLevel,
+                error_message: props.error_message,
+                file_uploaded: props.file_uploaded,
+                files_uploaded: props.files_uploaded,
+                object_url: props.object_url,
+                saved_message,
+            }
+            if !saved_message().is_empty() {
+                p { class: "text-center text-xs text-neutral-500 sm:text-sm", {saved_message} }
             }
         }
     }
@@ -0,0 +1,68 @@
+use dioxus::prelude::*;
+
+use crate::pdf::SplitDocumnet;
+
+use super::{input_file::FileUploaded, split_input_page_number_str::show_split_results};
+
+#[derive(PartialEq, Clone, Props)]
+pub struct SplitBlankPagesProps {
+    error_message: Signal<String>,
+    file_uploaded: Signal<Option<FileUploaded>>,
+    files_uploaded: Signal<Vec<FileUploaded>>,
+    object_url: Signal<String>,
+    saved_message: Signal<String>,
+}
+
+const SPLIT_ACTION: Asset = asset!("/assets/imgs/scissor-2-svgrepo-com.svg");
+const PREVIEW: Asset = asset!("/assets/imgs/preview-svgrepo-com.svg");
+
+// One file for every run of pages between blank pages
+#[component]
+pub fn SplitBlankPages(mut props: SplitBlankPagesProps) -> Element {
+    // Also split at scanned pages that are near-white
+    let mut is_check_images = use_signal(|| false);
+
+    let split_action_handler = move |_evt: Event<MouseData>| {
+        props.error_message.set(String::new());
+        props.saved_message.set(String::new());
+        if let Some(file) = props.file_uploaded.read().clone() {
+            let split_pdfs_result =
+                SplitDocumnet::split_pdf_by_blank_pages(&file.file_buffer, is_check_images());
+            show_split_results(
+                split_pdfs_result,
+                &file.get_filename(),
+                props.files_uploaded,
+                props.error_message,
+                props.saved_message,
+            );
+        }
+    };
+
+    rsx! {
+        div { class: "flex w-4/5 max-w-screen-md items-center justify-center p-1",
+            label { class: "mr-2", r#for: "split_blank_pages", "Blank pages: " }
+            label { class: "flex w-60 items-center gap-2 text-sm sm:w-80 sm:text-base",
+                input {
+                    id: "split_blank_pages",
+                    r#type: "checkbox",
+                    checked: is_check_images,
+                    onchange: move |evt: Event<FormData>| is_check_images.set(evt.checked()),
+                }
+                "Also near-white scanned pages"
+            }
+            img {
+                src: SPLIT_ACTION,
+                alt: "split_action",
+                class: "w-10 h-10 mx-2 cursor-pointer",
+                onclick: split_action_handler,
+            }
+            a {
+                title: "Preview PDF",
+                class: "hidden xl:block",
+                href: props.object_url,
+                target: "iframe_pdf",
+                img { src: PREVIEW, alt: "preview_pdf", class: "w-10 h-10" }
+            }
+        }
+    }
+}
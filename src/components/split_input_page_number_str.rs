@@ -1,21 +1,43 @@
 use dioxus::prelude::*;
+use lopdf::Document;
 
-use crate::{pdf::SplitDocumnet, utils::convert_vec_u8_to_pdf_blob};
+use crate::{
+    pdf::{OptimizeDocument, OrganizeDocument, PageRangeError, PageRanges, SplitDocumnet},
+    utils::{convert_vec_u8_to_pdf_blob, saved_bytes_message},
+};
 
 use super::input_file::FileUploaded;
 
+#[derive(PartialEq, Clone, Copy)]
+pub enum SplitMode {
+    // e.g. "1, 2-3, 5", one file for every item
+    PageNumbers,
+    // e.g. "2", one file for every 2 pages
+    FixedPages,
+    // e.g. "1", one file for every top level bookmark
+    OutlineLevel,
+    // e.g. "10", files of at most 10 MB
+    SizeLimit,
+    // e.g. "Invoice No\. (\d+)", a new file at every matching page
+    TextPattern,
+}
+
+// Upload portals count in decimal megabytes, stay under their limit
+const BYTES_PER_MB: usize = 1_000_000;
+
 #[derive(PartialEq, Clone, Props)]
 pub struct SplitInputPageNumberStr {
     #[props(into)]
     name: String,
     #[props(into)]
     example: String,
-    is_fiexed_page: bool,
+    split_mode: SplitMode,
     signal_page_numbers_str: Signal<String>,
     error_message: Signal<String>,
     file_uploaded: Signal<Option<FileUploaded>>,
     files_uploaded: Signal<Vec<FileUploaded>>,
     object_url: Signal<String>,
+    saved_message: Signal<String>,
 }
 
 const SPLIT_ACTION: Asset = asset!("/assets/imgs/scissor-2-svgrepo-com.svg");
@@ -23,16 +45,50 @@ const PREVIEW: Asset = asset!("/assets/imgs/preview-svgrepo-com.svg");
 
 #[component]
 pub fn SplitInputPageNumberStr(mut props: SplitInputPageNumberStr) -> Element {
+    // Page ranges are checked against the document while typing
+    let page_count = use_memo(move || {
+        if props.split_mode != SplitMode::PageNumbers {
+            return None;
+        }
+        props
+            .file_uploaded
+            .read()
+            .as_ref()
+            .and_then(|file| OrganizeDocument::page_count_from_mem(&file.file_buffer).ok())
+    });
+    let page_range_error = use_memo(move || -> Option<PageRangeError> {
+        if props.split_mode != SplitMode::PageNumbers {
+            return None;
+        }
+        let page_numbers_str = props.signal_page_numbers_str.read();
+        let result = PageRanges::parse(page_numbers_str.as_str()).and_then(|page_ranges| {
+            match page_count() {
+                Some(max_pages) => page_ranges.page_numbers(max_pages).map(|_| ()),
+                None => Ok(()),
+            }
+        });
+        match result {
+            Err(crate::pdf::Error::InValidPageRange(e)) => Some(e),
+            _ => None,
+        }
+    });
     let split_action_handler = move |_evt: Event<MouseData>| {
         props.error_message.set(String::new());
-        let check_split_page_numbers_str = if props.is_fiexed_page {
-            SplitDocumnet::is_valid_string_split_fixed_pages(
-                props.signal_page_numbers_str.read().as_str(),
-            )
-        } else {
-            SplitDocumnet::is_valid_string_split_pages(
+        props.saved_message.set(String::new());
+        // Shown under the input already
+        if page_range_error.read().is_some() {
+            return;
+        }
+        let check_split_page_numbers_str = match props.split_mode {
+            SplitMode::PageNumbers => SplitDocumnet::is_valid_string_split_pages(
                 props.signal_page_numbers_str.read().as_str(),
-            )
+            ),
+            SplitMode::FixedPages | SplitMode::OutlineLevel | SplitMode::SizeLimit => {
+                SplitDocumnet::is_valid_string_split_fixed_pages(
+                    props.signal_page_numbers_str.read().as_str(),
+                )
+            }
+            SplitMode::TextPattern => Ok(!props.signal_page_numbers_str.read().trim().is_empty()),
         };
         match check_split_page_numbers_str {
             Ok(check_split_page_numbers_str) => {
@@ -40,80 +96,57 @@ pub fn SplitInputPageNumberStr(mut props: SplitInputPageNumberStr) -> Element {
                     if let Some(file) = props.file_uploaded.read().clone() {
                         let split_file_name = file.get_filename();
                         let buffer = file.file_buffer;
-                        let split_pdfs_result = if props.is_fiexed_page {
-                            let page_number_u32: u32 = props
-                                .signal_page_numbers_str
-                                .read()
-                                .as_str()
-                                .parse()
-                                .unwrap_or(1);
-                            SplitDocumnet::split_pdf_from_mem_fixed_page(&buffer, page_number_u32)
-                        } else {
-                            SplitDocumnet::split_pdf_from_mem(
+                        let split_pdfs_result = match props.split_mode {
+                            SplitMode::PageNumbers => SplitDocumnet::split_pdf_from_mem(
                                 &buffer,
                                 props.signal_page_numbers_str.read().as_str(),
-                            )
-                        };
-
-                        match split_pdfs_result {
-                            Ok(mut docs) => {
-                                props
-                                    .files_uploaded
+                            ),
+                            SplitMode::FixedPages => {
+                                let page_number_u32: u32 = props
+                                    .signal_page_numbers_str
                                     .read()
-                                    .iter()
-                                    .for_each(|file_uploaded| {
-                                        file_uploaded.revoke_object_url();
-                                    });
-                                props.files_uploaded.set(Vec::new());
-                                docs.iter_mut().for_each(|(doc, page)| {
-                                    let mut buffer: Vec<u8> = Vec::new();
-                                    let write_to_buffer = doc.save_to(&mut buffer);
-                                    match write_to_buffer {
-                                        Ok(_) => {
-                                            let file_data_blob =
-                                                convert_vec_u8_to_pdf_blob(&buffer);
-                                            match file_data_blob {
-                                                Ok(blob) => {
-                                                    let file_uploaded = FileUploaded::new(
-                                                        format!("{split_file_name}-{page}.pdf"),
-                                                        blob,
-                                                        buffer,
-                                                    );
-                                                    match file_uploaded {
-                                                        Ok(file) => {
-                                                            props.files_uploaded.write().push(file);
-                                                        }
-                                                        Err(e) => {
-                                                            props.error_message.set(format!(
-                                                                "Error (split, 88): {e}"
-                                                            ));
-                                                        }
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    props
-                                                        .error_message
-                                                        .set(format!("Error (split, 96): {e}"));
-                                                }
-                                            }
-                                        }
-                                        Err(std_io_e) => {
-                                            props
-                                                .error_message
-                                                .set(format!("Error (split, 103): {std_io_e}"));
-                                        }
-                                    }
-                                });
+                                    .as_str()
+                                    .parse()
+                                    .unwrap_or(1);
+                                SplitDocumnet::split_pdf_from_mem_fixed_page(
+                                    &buffer,
+                                    page_number_u32,
+                                )
                             }
-                            Err(split_pdfs_e) => match split_pdfs_e {
-                                crate::pdf::Error::Lopdf(error) => props
-                                    .error_message
-                                    .set(format!("Error::Lopdf (split, 111): {error}")),
-                                _ => props
-                                    .error_message
-                                    .set(format!("Error (split, 114): {split_pdfs_e}")),
-                            },
-                        }
+                            SplitMode::OutlineLevel => {
+                                let level: u32 = props
+                                    .signal_page_numbers_str
+                                    .read()
+                                    .as_str()
+                                    .parse()
+                                    .unwrap_or(1);
+                                SplitDocumnet::split_pdf_by_outline(&buffer, level)
+                            }
+                            SplitMode::SizeLimit => {
+                                let max_size_mb: usize = props
+                                    .signal_page_numbers_str
+                                    .read()
+                                    .as_str()
+                                    .parse()
+                                    .unwrap_or(1);
+                                SplitDocumnet::split_pdf_by_size(
+                                    &buffer,
+                                    max_size_mb.saturating_mul(BYTES_PER_MB),
+                                )
+                            }
+                            SplitMode::TextPattern => SplitDocumnet::split_pdf_by_text_pattern(
+                                &buffer,
+                                props.signal_page_numbers_str.read().as_str(),
+                            ),
+                        };
+
+                        show_split_results(
+                            split_pdfs_result,
+                            &split_file_name,
+                            props.files_uploaded,
+                            props.error_message,
+                            props.saved_message,
+                        );
                     };
                 } else {
                     props
@@ -125,12 +158,35 @@ pub fn SplitInputPageNumberStr(mut props: SplitInputPageNumberStr) -> Element {
         };
     };
 
+    // (before, offending token, after) of the input and the error message
+    let page_range_error_parts = page_range_error().map(|e| {
+        let page_numbers_str = props.signal_page_numbers_str.read();
+        let (start, end) = e.span(page_numbers_str.as_str());
+        let part = |start: usize, end: usize| {
+            page_numbers_str
+                .get(start..end)
+                .unwrap_or_default()
+                .to_string()
+        };
+        (
+            part(0, start),
+            part(start, end),
+            part(end, page_numbers_str.len()),
+            e.to_string(),
+        )
+    });
+    let input_border = if page_range_error_parts.is_some() {
+        "border-red-400"
+    } else {
+        "border-neutral-300"
+    };
+
     rsx! {
         div { class: "flex w-4/5 max-w-screen-md items-center justify-center p-1",
             label { class: "mr-2", r#for: "split_str", "{props.name}" }
             input {
                 id: "split_str",
-                class: "text-base rounded-md border-2 w-60 sm:w-80 border-neutral-300 p-2",
+                class: "text-base rounded-md border-2 w-60 sm:w-80 {input_border} p-2",
                 r#type: "text",
                 placeholder: props.example,
                 value: props.signal_page_numbers_str,
@@ -151,5 +207,106 @@ pub fn SplitInputPageNumberStr(mut props: SplitInputPageNumberStr) -> Element {
                 img { src: PREVIEW, alt: "preview_pdf", class: "w-10 h-10" }
             }
         }
+        if let Some((before, token, after, message)) = page_range_error_parts {
+            div { class: "flex w-4/5 max-w-screen-md flex-col items-center pb-1",
+                p { class: "whitespace-pre font-mono text-sm",
+                    span { "{before}" }
+                    mark { class: "inline-block min-w-2 rounded-sm bg-red-200 text-red-700",
+                        "{token}"
+                    }
+                    span { "{after}" }
+                }
+                p { class: "text-xs text-red-600 sm:text-sm", "{message}" }
+            }
+        }
+    }
+}
+
+// Replace the results with the split documents, or show why the document cannot be split
+pub fn show_split_results(
+    split_pdfs_result: crate::pdf::Result<Vec<(Document, String)>>,
+    split_file_name: &str,
+    mut files_uploaded: Signal<Vec<FileUploaded>>,
+    mut error_message: Signal<String>,
+    mut saved_message: Signal<String>,
+) {
+    match split_pdfs_result {
+        Ok(mut docs) => {
+            let saved_bytes: usize = docs
+                .iter_mut()
+                .map(|(doc, _)| OptimizeDocument::optimize(doc))
+                .sum();
+            saved_message.set(saved_bytes_message(saved_bytes));
+            files_uploaded.read().iter().for_each(|file_uploaded| {
+                file_uploaded.revoke_object_url();
+            });
+            files_uploaded.set(Vec::new());
+            docs.iter_mut().for_each(|(doc, page)| {
+                let mut buffer: Vec<u8> = Vec::new();
+                let write_to_buffer = doc.save_to(&mut buffer);
+                match write_to_buffer {
+                    Ok(_) => {
+                        let file_data_blob = convert_vec_u8_to_pdf_blob(&buffer);
+                        match file_data_blob {
+                            Ok(blob) => {
+                                let file_uploaded = FileUploaded::new(
+                                    format!("{split_file_name}-{page}.pdf"),
+                                    blob,
+                                    buffer,
+                                );
+                                match file_uploaded {
+                                    Ok(file) => {
+                                        files_uploaded.write().push(file);
+                                    }
+                                    Err(e) => {
+                                        error_message.set(format!("Error (split, 88): {e}"));
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                error_message.set(format!("Error (split, 96): {e}"));
+                            }
+                        }
+                    }
+                    Err(std_io_e) => {
+                        error_message.set(format!("Error (split, 103): {std_io_e}"));
+                    }
+                }
+            });
+        }
+        Err(split_pdfs_e) => match split_pdfs_e {
+            crate::pdf::Error::Lopdf(error) => {
+                error_message.set(format!("Error::Lopdf (split, 111): {error}"))
+            }
+            crate::pdf::Error::EncryptedDocument => {
+                error_message.set(format!("{split_file_name} is password protected"))
+            }
+            crate::pdf::Error::OutlineLevelNotFound => {
+                error_message.set("no bookmarks at this level".to_string())
+            }
+            crate::pdf::Error::CannotCreateRegex => {
+                error_message.set("invalid text pattern".to_string())
+            }
+            crate::pdf::Error::TextPatternNotFound => {
+                error_message.set("no page matches the text pattern".to_string())
+            }
+            crate::pdf::Error::BlankPageNotFound => {
+                error_message.set("no blank pages to split at".to_string())
+            }
+            crate::pdf::Error::CannotRemoveAllPages => {
+                error_message.set("every page is blank".to_string())
+            }
+            crate::pdf::Error::PagesOverSizeLimit(page_numbers) => {
+                let page_numbers: Vec<String> = page_numbers
+                    .iter()
+                    .map(|page_number| page_number.to_string())
+                    .collect();
+                error_message.set(format!(
+                    "pages larger than the size limit on their own: {}",
+                    page_numbers.join(", ")
+                ))
+            }
+            _ => error_message.set(format!("Error (split, 114): {split_pdfs_e}")),
+        },
     }
 }
@@ -0,0 +1,162 @@
+use dioxus::prelude::*;
+
+use crate::pdf::{Error, OrganizeDocument, SplitDocumnet};
+
+use super::{input_file::FileUploaded, thumbnails::use_thumbnail_urls};
+
+// Pages shown at first and added by "Show more pages"
+const PAGES_PER_LOAD: usize = 30;
+
+#[derive(PartialEq, Clone, Props)]
+pub struct SplitPagePickerProps {
+    file_uploaded: Signal<Option<FileUploaded>>,
+    signal_page_numbers_str: Signal<String>,
+}
+
+#[component]
+pub fn SplitPagePicker(mut props: SplitPagePickerProps) -> Element {
+    let file_url = use_memo(move || {
+        props
+            .file_uploaded
+            .read()
+            .as_ref()
+            .map(|file| file.get_url())
+            .unwrap_or_default()
+    });
+    let page_count = use_memo(move || {
+        file_url.read();
+        props
+            .file_uploaded
+            .peek()
+            .as_ref()
+            .and_then(|file| OrganizeDocument::page_count_from_mem(&file.file_buffer).ok())
+            .unwrap_or(0)
+    });
+    // Thumbnails are rendered for the shown pages only, more are shown on demand
+    let mut shown_pages = use_signal(|| PAGES_PER_LOAD);
+    let thumbnail_urls = use_thumbnail_urls(
+        file_url,
+        move || {
+            props
+                .file_uploaded
+                .peek()
+                .as_ref()
+                .filter(|file| !file.is_locked)
+                .map(|file| file.file_buffer.clone())
+        },
+        shown_pages,
+    );
+    // The picked files are the split string, typed or picked. None while it does not fit the document
+    let files = use_memo(move || {
+        SplitDocumnet::picked_files(&props.signal_page_numbers_str.read(), page_count()).ok()
+    });
+    // First page of the range being picked
+    let mut range_start: Signal<Option<u32>> = use_signal(|| None);
+    let mut notice = use_signal(String::new);
+    use_effect(move || {
+        page_count.read();
+        shown_pages.set(PAGES_PER_LOAD);
+        range_start.set(None);
+        notice.set(String::new());
+    });
+
+    // Click the first and then the last page of an output file, click a picked page to drop its file
+    let mut pick_page = move |page_number: u32| {
+        let Some(files) = files() else {
+            notice.set("Fix the pages above to pick pages".to_string());
+            return;
+        };
+        notice.set(String::new());
+        let split_page_numbers_str = props.signal_page_numbers_str.peek().clone();
+        let result = if let Some(first) = range_start() {
+            range_start.set(None);
+            SplitDocumnet::add_picked_range(
+                &split_page_numbers_str,
+                first,
+                page_number,
+                page_count(),
+            )
+        } else if files.iter().any(|file| file.contains(&page_number)) {
+            SplitDocumnet::remove_picked_page(&split_page_numbers_str, page_number, page_count())
+        } else {
+            range_start.set(Some(page_number));
+            return;
+        };
+        match result {
+            Ok(split_page_numbers_str) => props.signal_page_numbers_str.set(split_page_numbers_str),
+            Err(Error::PickedPagesOverlap(page_numbers)) => notice.set(format!(
+                "Page {} is in a file already, pick another range",
+                page_numbers[0]
+            )),
+            Err(e) => notice.set(format!("Error (split_page_picker, pick): {e}")),
+        }
+    };
+
+    let page_count = page_count();
+    let last_shown_page = page_count.min(shown_pages() as u32);
+    rsx! {
+        section { class: "mt-2 flex w-4/5 max-w-screen-md flex-col items-center",
+            p { class: "text-center text-xs text-neutral-500 sm:text-sm",
+                "Click the first and the last page of every file, click a picked page to remove its file"
+            }
+            div { class: "mt-1 grid max-h-96 w-full grid-cols-3 gap-2 overflow-auto rounded-md border-2 border-neutral-300 p-2 sm:grid-cols-5 md:grid-cols-6",
+                for page_number in 1..last_shown_page + 1 {
+                    {
+                        // A typed string may put a page in several files, e.g. "1-3, odd"
+                        let file_numbers = files()
+                            .unwrap_or_default()
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, file)| file.contains(&page_number))
+                            .map(|(idx, _)| (idx + 1).to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ");
+                        let border = if range_start() == Some(page_number) {
+                            "border-sky-500"
+                        } else if !file_numbers.is_empty() {
+                            "border-red-400"
+                        } else {
+                            "border-neutral-200"
+                        };
+                        let thumbnail_url = thumbnail_urls.read().get(page_number as usize - 1).cloned();
+                        rsx! {
+                            button {
+                                key: "{page_number}",
+                                class: "relative flex flex-col items-center rounded-md border-2 p-1 {border}",
+                                title: "Page {page_number}",
+                                onclick: move |_evt| pick_page(page_number),
+                                if let Some(thumbnail_url) = thumbnail_url {
+                                    img {
+                                        class: "h-20 sm:h-24",
+                                        src: "{thumbnail_url}",
+                                        alt: "Page {page_number}",
+                                    }
+                                } else {
+                                    div { class: "flex h-20 w-14 items-center justify-center bg-neutral-100 sm:h-24 sm:w-16",
+                                        "{page_number}"
+                                    }
+                                }
+                                span { class: "text-xs", "Page {page_number}" }
+                                if !file_numbers.is_empty() {
+                                    span { class: "absolute right-1 top-1 rounded-md bg-red-400 px-1 text-xs text-white",
+                                        "File {file_numbers}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if last_shown_page < page_count {
+                button {
+                    class: "mt-2 w-1/2 rounded-md bg-neutral-200 p-2 text-sm hover:bg-neutral-400 focus:bg-neutral-400 sm:text-base",
+                    onclick: move |_evt| shown_pages += PAGES_PER_LOAD,
+                    "Show more pages"
+                }
+            }
+            if !notice().is_empty() {
+                p { class: "text-center text-xs text-red-500 sm:text-sm", {notice} }
+            }
+        }
+    }
+}
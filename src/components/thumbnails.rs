@@ -0,0 +1,115 @@
+use dioxus::prelude::*;
+use web_sys::Url;
+
+use crate::{
+    pdf::ThumbnailRenderer,
+    utils::{convert_vec_u8_to_png_blob, yield_to_browser},
+};
+
+use super::input_file::FileUploaded;
+
+// Width in pixels of the page thumbnails
+const THUMBNAIL_WIDTH: f32 = 96.0;
+// The strip under a listed file previews its first pages only
+const MAX_STRIP_THUMBNAILS: usize = 12;
+
+// Object URLs of PNG thumbnails of the first `max_pages` pages of the file behind `file_url`.
+// They are rendered once the component is shown and again when the URL changes, e.g. after unlocking,
+// and revoked when the component is dropped. Pages are rendered one at a time with a yield to the
+// browser before each, so the UI stays responsive, and raising `max_pages` renders only the new pages.
+// Thumbnails are a preview only, they stay empty while the file is locked and stop at the first page
+// that cannot be rendered.
+pub fn use_thumbnail_urls(
+    file_url: Memo<String>,
+    mut file_buffer: impl FnMut() -> Option<Vec<u8>> + 'static,
+    max_pages: Signal<usize>,
+) -> Signal<Vec<String>> {
+    let mut thumbnail_urls: Signal<Vec<String>> = use_signal(Vec::new);
+    let mut rendered_url = use_signal(String::new);
+    let _thumbnails = use_resource(move || {
+        let url = file_url();
+        let max_pages = max_pages();
+        let file_buffer = file_buffer();
+        async move {
+            if *rendered_url.peek() != url {
+                revoke_thumbnail_urls(&mut thumbnail_urls.write());
+                rendered_url.set(url);
+            }
+            let Some(renderer) =
+                file_buffer.and_then(|file_buffer| ThumbnailRenderer::new(&file_buffer).ok())
+            else {
+                return;
+            };
+            let start = thumbnail_urls.peek().len();
+            for page_idx in start..renderer.page_count().min(max_pages) {
+                yield_to_browser().await;
+                let url = renderer
+                    .render_thumbnail(page_idx, THUMBNAIL_WIDTH)
+                    .ok()
+                    .and_then(|png| convert_vec_u8_to_png_blob(&png).ok())
+                    .and_then(|blob| Url::create_object_url_with_blob(&blob).ok());
+                match url {
+                    Some(url) => thumbnail_urls.write().push(url),
+                    None => break,
+                }
+            }
+        }
+    });
+    use_drop(move || revoke_thumbnail_urls(&mut thumbnail_urls.write()));
+
+    thumbnail_urls
+}
+
+fn revoke_thumbnail_urls(thumbnail_urls: &mut Vec<String>) {
+    for url in thumbnail_urls.drain(..) {
+        let _revoke_oveject_url = Url::revoke_object_url(&url);
+    }
+}
+
+#[derive(PartialEq, Clone, Props)]
+pub struct ThumbnailsProps {
+    files_uploaded: Signal<Vec<FileUploaded>>,
+    idx: usize,
+}
+
+// A strip with the first pages of a listed file
+#[component]
+pub fn Thumbnails(props: ThumbnailsProps) -> Element {
+    let file_url = use_memo(move || {
+        props
+            .files_uploaded
+            .read()
+            .get(props.idx)
+            .map(|file_uploaded| file_uploaded.get_url())
+            .unwrap_or_default()
+    });
+    let max_pages = use_signal(|| MAX_STRIP_THUMBNAILS);
+    let urls = use_thumbnail_urls(
+        file_url,
+        move || {
+            props
+                .files_uploaded
+                .peek()
+                .get(props.idx)
+                .filter(|file_uploaded| !file_uploaded.is_locked)
+                .map(|file_uploaded| file_uploaded.file_buffer.clone())
+        },
+        max_pages,
+    );
+
+    rsx! {
+        if !urls.read().is_empty() {
+            div { class: "flex flex-row gap-1 overflow-x-auto px-1 pb-1",
+                for (idx , url) in urls.read().iter().enumerate() {
+                    img {
+                        key: "{url}",
+                        class: "h-16 border border-neutral-300 sm:h-20",
+                        src: "{url}",
+                        alt: "Page {idx + 1}",
+                        title: "Page {idx + 1}",
+                    }
+                }
+            }
+        }
+    }
+}
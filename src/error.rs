@@ -5,6 +5,13 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     // JsValue from web_sys crate
     JsValue(String),
+
+    // Error from crate::pdf, kept as a message as lopdf errors are not Clone
+    Pdf(String),
+    IncorrectPassword,
+
+    // Error from the zip crate
+    Zip(String),
 }
 
 // region:    --- Error Boilerplate
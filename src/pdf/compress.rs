@@ -0,0 +1,547 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, ImageFormat, RgbImage};
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use std::collections::HashMap;
+
+use super::{DecryptDocument, Error, OptimizeDocument, Result};
+
+// Content stream transformation matrix [a b c d e f]
+type Matrix = [f32; 6];
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+const POINTS_PER_INCH: f32 = 72.0;
+// Forms drawn inside forms are followed this deep
+const MAX_FORM_DEPTH: usize = 16;
+
+pub struct CompressOptions {
+    // Images shown at a higher resolution are downsampled to this one
+    pub target_dpi: u32,
+    // JPEG quality of the re-encoded images, 1 to 100
+    pub jpeg_quality: u8,
+}
+
+impl Default for CompressOptions {
+    fn default() -> Self {
+        Self {
+            target_dpi: 150,
+            jpeg_quality: 75,
+        }
+    }
+}
+
+pub struct CompressDocument;
+
+impl CompressDocument {
+    // Downsample images above the target DPI and re-encode them and the JPEG images as JPEG,
+    // then compress the other streams and drop unused and duplicate objects.
+    // An image is only replaced when the result is smaller.
+    pub fn compress_pdf_from_mem(buffer: &[u8], options: &CompressOptions) -> Result<Document> {
+        if options.target_dpi == 0 || !(1..=100).contains(&options.jpeg_quality) {
+            return Err(Error::InValidCompressOptions);
+        }
+        let mut doc = DecryptDocument::load_mem(buffer, "")?;
+        let display_sizes = Self::_image_display_sizes(&doc);
+        // The color space and its ICC profile may be indirect, resolve them before borrowing the images
+        let images: Vec<(ObjectId, u8)> = doc
+            .objects
+            .iter()
+            .filter_map(|(object_id, object)| {
+                let Object::Stream(stream) = object else {
+                    return None;
+                };
+                if !matches!(
+                    stream.dict.get(b"Subtype").and_then(Object::as_name),
+                    Ok(b"Image")
+                ) {
+                    return None;
+                }
+                Some((*object_id, Self::_color_components(&doc, &stream.dict)?))
+            })
+            .collect();
+        for (object_id, color_components) in images {
+            if let Ok(Object::Stream(stream)) = doc.get_object_mut(object_id) {
+                Self::_compress_image(
+                    stream,
+                    color_components,
+                    display_sizes.get(&object_id).copied(),
+                    options,
+                );
+            }
+        }
+        doc.compress();
+        OptimizeDocument::optimize(&mut doc);
+
+        Ok(doc)
+    }
+
+    // Largest size in points every image is drawn at on the pages, including in form XObjects
+    fn _image_display_sizes(doc: &Document) -> HashMap<ObjectId, (f32, f32)> {
+        let mut display_sizes: HashMap<ObjectId, (f32, f32)> = HashMap::new();
+        for page_id in doc.page_iter() {
+            let Ok(content) = doc.get_page_content(page_id) else {
+                continue;
+            };
+            let xobjects = Self::_page_xobjects(doc, page_id);
+            Self::_content_display_sizes(doc, &content, &xobjects, IDENTITY, 0, &mut display_sizes);
+        }
+
+        display_sizes
+    }
+
+    // Follow the CTM through a content stream starting at `ctm`. A form is drawn with its /Matrix
+    // applied to the CTM of its "Do", and its content is followed the same way.
+    fn _content_display_sizes(
+        doc: &Document,
+        content: &[u8],
+        xobjects: &HashMap<Vec<u8>, ObjectId>,
+        mut ctm: Matrix,
+        depth: usize,
+        display_sizes: &mut HashMap<ObjectId, (f32, f32)>,
+    ) {
+        let Ok(content) = Content::decode(content) else {
+            return;
+        };
+        let mut saved_ctms: Vec<Matrix> = Vec::new();
+        for operation in content.operations {
+            match operation.operator.as_str() {
+                "q" => saved_ctms.push(ctm),
+                "Q" => ctm = saved_ctms.pop().unwrap_or(IDENTITY),
+                "cm" => {
+                    if let Some(matrix) = Self::_matrix(&operation.operands) {
+                        ctm = Self::_multiply(&matrix, &ctm);
+                    }
+                }
+                "Do" => {
+                    let Some(object_id) = operation
+                        .operands
+                        .first()
+                        .and_then(|name| name.as_name().ok())
+                        .and_then(|name| xobjects.get(name))
+                    else {
+                        continue;
+                    };
+                    let Ok(stream) = doc.get_object(*object_id).and_then(Object::as_stream) else {
+                        continue;
+                    };
+                    match stream.dict.get(b"Subtype").and_then(Object::as_name) {
+                        Ok(b"Image") => {
+                            // The image is the unit square transformed by the CTM
+                            let width = ctm[0].hypot(ctm[1]);
+                            let height = ctm[2].hypot(ctm[3]);
+                            let display_size =
+                                display_sizes.entry(*object_id).or_insert((0.0, 0.0));
+                            display_size.0 = display_size.0.max(width);
+                            display_size.1 = display_size.1.max(height);
+                        }
+                        // Forms can draw each other, a cycle stops at the depth limit
+                        Ok(b"Form") if depth < MAX_FORM_DEPTH => {
+                            let matrix = stream
+                                .dict
+                                .get(b"Matrix")
+                                .and_then(Object::as_array)
+                                .ok()
+                                .and_then(|operands| Self::_matrix(operands))
+                                .unwrap_or(IDENTITY);
+                            // A form without resources uses the ones of the content drawing it
+                            let form_xobjects = match stream.dict.get_deref(b"Resources", doc) {
+                                Ok(Object::Dictionary(resources)) => {
+                                    let mut form_xobjects = HashMap::new();
+                                    Self::_add_xobjects(doc, resources, &mut form_xobjects);
+                                    form_xobjects
+                                }
+                                _ => xobjects.clone(),
+                            };
+                            let form_content = match stream.dict.has(b"Filter") {
+                                true => stream.decompressed_content().ok(),
+                                false => Some(stream.content.clone()),
+                            };
+                            if let Some(form_content) = form_content {
+                                Self::_content_display_sizes(
+                                    doc,
+                                    &form_content,
+                                    &form_xobjects,
+                                    Self::_multiply(&matrix, &ctm),
+                                    depth + 1,
+                                    display_sizes,
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // XObject names of the page resources, including inherited ones
+    fn _page_xobjects(doc: &Document, page_id: ObjectId) -> HashMap<Vec<u8>, ObjectId> {
+        let mut xobjects: HashMap<Vec<u8>, ObjectId> = HashMap::new();
+        let Ok((resources, resource_ids)) = doc.get_page_resources(page_id) else {
+            return xobjects;
+        };
+        let resources = resources.into_iter().chain(
+            resource_ids
+                .iter()
+                .filter_map(|resource_id| doc.get_dictionary(*resource_id).ok()),
+        );
+        for resources in resources {
+            Self::_add_xobjects(doc, resources, &mut xobjects);
+        }
+
+        xobjects
+    }
+
+    // The first resources with a name win, the page ones come before the inherited ones
+    fn _add_xobjects(
+        doc: &Document,
+        resources: &Dictionary,
+        xobjects: &mut HashMap<Vec<u8>, ObjectId>,
+    ) {
+        let Ok(xobject_dict) = resources
+            .get_deref(b"XObject", doc)
+            .and_then(Object::as_dict)
+        else {
+            return;
+        };
+        for (name, xobject) in xobject_dict.iter() {
+            if let Ok(object_id) = xobject.as_reference() {
+                xobjects.entry(name.clone()).or_insert(object_id);
+            }
+        }
+    }
+
+    // The six numbers of a "cm" operator or a /Matrix array
+    fn _matrix(operands: &[Object]) -> Option<Matrix> {
+        let operands: Vec<f32> = operands
+            .iter()
+            .filter_map(|operand| operand.as_float().ok())
+            .collect();
+        Matrix::try_from(operands.as_slice()).ok()
+    }
+
+    // m × ctm, the matrix of a "cm" operator applied to the current one
+    fn _multiply(m: &Matrix, ctm: &Matrix) -> Matrix {
+        [
+            m[0] * ctm[0] + m[1] * ctm[2],
+            m[0] * ctm[1] + m[1] * ctm[3],
+            m[2] * ctm[0] + m[3] * ctm[2],
+            m[2] * ctm[1] + m[3] * ctm[3],
+            m[4] * ctm[0] + m[5] * ctm[2] + ctm[4],
+            m[4] * ctm[1] + m[5] * ctm[3] + ctm[5],
+        ]
+    }
+
+    // Images that cannot be decoded, e.g. CMYK, indexed or 1 bit, are left as they are
+    fn _compress_image(
+        stream: &mut Stream,
+        color_components: u8,
+        display_size: Option<(f32, f32)>,
+        options: &CompressOptions,
+    ) -> Option<()> {
+        let dict = &stream.dict;
+        let width = u32::try_from(dict.get(b"Width").ok()?.as_i64().ok()?).ok()?;
+        let height = u32::try_from(dict.get(b"Height").ok()?.as_i64().ok()?).ok()?;
+        let is_image_mask = dict
+            .get(b"ImageMask")
+            .and_then(Object::as_bool)
+            .unwrap_or(false);
+        // A color key mask matches exact sample values, JPEG would break it
+        let is_color_key_mask = matches!(dict.get(b"Mask"), Ok(Object::Array(_)));
+        if is_image_mask || is_color_key_mask || width == 0 || height == 0 {
+            return None;
+        }
+        if dict
+            .get(b"BitsPerComponent")
+            .and_then(Object::as_i64)
+            .ok()?
+            != 8
+        {
+            return None;
+        }
+        // lopdf reports a missing filter as an error
+        let filters = match stream.dict.has(b"Filter") {
+            true => stream.filters().ok()?,
+            false => Vec::new(),
+        };
+        let is_jpeg = filters == ["DCTDecode"];
+
+        let (new_width, new_height) = match display_size {
+            Some((display_width, display_height)) => {
+                let dpi = options.target_dpi as f32;
+                let target_width = (display_width / POINTS_PER_INCH * dpi).ceil().max(1.0);
+                let target_height = (display_height / POINTS_PER_INCH * dpi).ceil().max(1.0);
+                let scale = (target_width / width as f32)
+                    .max(target_height / height as f32)
+                    .min(1.0);
+                (
+                    ((width as f32 * scale).round() as u32).max(1),
+                    ((height as f32 * scale).round() as u32).max(1),
+                )
+            }
+            None => (width, height),
+        };
+        let is_downsample = new_width < width || new_height < height;
+        if !is_downsample && !is_jpeg {
+            return None;
+        }
+
+        let image = if is_jpeg {
+            let image =
+                image::load_from_memory_with_format(&stream.content, ImageFormat::Jpeg).ok()?;
+            match color_components {
+                1 => DynamicImage::ImageLuma8(image.into_luma8()),
+                _ => DynamicImage::ImageRgb8(image.into_rgb8()),
+            }
+        } else {
+            let samples = if filters.is_empty() {
+                stream.content.clone()
+            } else {
+                // lopdf refuses to decode image streams, decode a copy that is not one
+                let mut raw_stream = stream.clone();
+                raw_stream.dict.remove(b"Subtype");
+                raw_stream.decompressed_content().ok()?
+            };
+            match color_components {
+                1 => DynamicImage::ImageLuma8(GrayImage::from_raw(width, height, samples)?),
+                _ => DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, samples)?),
+            }
+        };
+        let image = if is_downsample {
+            image.resize_exact(new_width, new_height, FilterType::Triangle)
+        } else {
+            image
+        };
+
+        let mut jpeg: Vec<u8> = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, options.jpeg_quality)
+            .encode_image(&image)
+            .ok()?;
+        if jpeg.len() >= stream.content.len() {
+            return None;
+        }
+        stream.dict.set("Width", new_width as i64);
+        stream.dict.set("Height", new_height as i64);
+        stream.dict.set("Filter", "DCTDecode");
+        stream.dict.remove(b"DecodeParms");
+        stream.set_content(jpeg);
+        // Already compressed, Document::compress must not deflate it again
+        stream.allows_compression = false;
+
+        Some(())
+    }
+
+    // 1 for gray and 3 for RGB color spaces, None for the others
+    fn _color_components(doc: &Document, dict: &Dictionary) -> Option<u8> {
+        match dict.get_deref(b"ColorSpace", doc).ok()? {
+            Object::Name(name) => match name.as_slice() {
+                b"DeviceGray" | b"CalGray" => Some(1),
+                b"DeviceRGB" | b"CalRGB" => Some(3),
+                _ => None,
+            },
+            Object::Array(color_space) => match color_space.first()?.as_name().ok()? {
+                b"CalGray" => Some(1),
+                b"CalRGB" => Some(3),
+                // The number of components is in the ICC profile stream, only gray and RGB
+                // profiles are supported
+                b"ICCBased" => {
+                    let (_, profile) = doc.dereference(color_space.get(1)?).ok()?;
+                    match profile
+                        .as_stream()
+                        .ok()?
+                        .dict
+                        .get_deref(b"N", doc)
+                        .ok()?
+                        .as_i64()
+                        .ok()?
+                    {
+                        1 => Some(1),
+                        3 => Some(3),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{create_document, save_to_buffer, set_page_content};
+    use lopdf::dictionary;
+
+    // Add a gray image to the first page and draw it `display_size` points wide and high
+    fn add_gray_image(doc: &mut Document, size: u32, display_size: u32) -> ObjectId {
+        // Noise from a linear congruential generator, deflate cannot shrink it
+        let mut seed: u32 = 1;
+        let samples: Vec<u8> = (0..size * size)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        let image = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => size as i64,
+                "Height" => size as i64,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 8,
+            },
+            samples,
+        );
+        let image_id = doc.add_object(image);
+        let page_id = doc.get_pages()[&1];
+        doc.get_dictionary_mut(page_id).unwrap().set(
+            "Resources",
+            dictionary! { "XObject" => dictionary! { "Im1" => image_id } },
+        );
+        set_page_content(
+            doc,
+            1,
+            format!("q {display_size} 0 0 {display_size} 100 100 cm /Im1 Do Q").as_bytes(),
+        );
+
+        image_id
+    }
+
+    fn image_dict(doc: &Document) -> &Dictionary {
+        doc.objects
+            .values()
+            .find_map(|object| match object {
+                Object::Stream(stream) if stream.dict.has(b"Width") => Some(&stream.dict),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn compress_pdf_from_mem_ok_1() {
+        // 600 px over one inch is 600 DPI
+        let mut doc = create_document(1);
+        add_gray_image(&mut doc, 600, 72);
+        let buffer = save_to_buffer(&mut doc);
+        let result =
+            CompressDocument::compress_pdf_from_mem(&buffer, &CompressOptions::default()).unwrap();
+        let dict = image_dict(&result);
+        assert_eq!(Some(150), dict.get(b"Width").and_then(Object::as_i64).ok());
+        assert_eq!(Some(150), dict.get(b"Height").and_then(Object::as_i64).ok());
+        assert_eq!(
+            Some(b"DCTDecode".as_slice()),
+            dict.get(b"Filter").and_then(Object::as_name).ok()
+        );
+        assert!(save_to_buffer(&mut result.clone()).len() < buffer.len());
+    }
+    #[test]
+    fn compress_pdf_from_mem_ok_2() {
+        // 100 px over two inches is already below the target
+        let mut doc = create_document(1);
+        add_gray_image(&mut doc, 100, 144);
+        let buffer = save_to_buffer(&mut doc);
+        let result =
+            CompressDocument::compress_pdf_from_mem(&buffer, &CompressOptions::default()).unwrap();
+        let dict = image_dict(&result);
+        assert_eq!(Some(100), dict.get(b"Width").and_then(Object::as_i64).ok());
+        assert_ne!(
+            Some(b"DCTDecode".as_slice()),
+            dict.get(b"Filter").and_then(Object::as_name).ok()
+        );
+    }
+    #[test]
+    fn compress_pdf_from_mem_ok_3() {
+        let buffer = save_to_buffer(&mut create_document(1));
+        let options = CompressOptions {
+            target_dpi: 0,
+            jpeg_quality: 75,
+        };
+        let result = CompressDocument::compress_pdf_from_mem(&buffer, &options);
+        assert!(matches!(result, Err(Error::InValidCompressOptions)));
+    }
+    #[test]
+    fn compress_pdf_from_mem_ok_4() {
+        // Indirect ICCBased gray color space with an indirect profile
+        let mut doc = create_document(1);
+        let image_id = add_gray_image(&mut doc, 600, 72);
+        let profile_id = doc.add_object(Stream::new(dictionary! { "N" => 1 }, Vec::new()));
+        let color_space_id = doc.add_object(vec![Object::from("ICCBased"), profile_id.into()]);
+        let image = doc
+            .get_object_mut(image_id)
+            .unwrap()
+            .as_stream_mut()
+            .unwrap();
+        image.dict.set("ColorSpace", color_space_id);
+        let buffer = save_to_buffer(&mut doc);
+        let result =
+            CompressDocument::compress_pdf_from_mem(&buffer, &CompressOptions::default()).unwrap();
+        let dict = image_dict(&result);
+        assert_eq!(Some(150), dict.get(b"Width").and_then(Object::as_i64).ok());
+        assert_eq!(
+            Some(b"DCTDecode".as_slice()),
+            dict.get(b"Filter").and_then(Object::as_name).ok()
+        );
+    }
+    #[test]
+    fn compress_pdf_from_mem_ok_5() {
+        // ICCBased RGB color space with an indirect profile
+        let mut doc = create_document(1);
+        let image_id = add_gray_image(&mut doc, 600, 72);
+        let profile_id = doc.add_object(Stream::new(dictionary! { "N" => 3 }, Vec::new()));
+        let image = doc
+            .get_object_mut(image_id)
+            .unwrap()
+            .as_stream_mut()
+            .unwrap();
+        let samples: Vec<u8> = image
+            .content
+            .iter()
+            .flat_map(|&v| [v, v / 2, 255 - v])
+            .collect();
+        image.set_content(samples);
+        image.dict.set(
+            "ColorSpace",
+            vec![Object::from("ICCBased"), profile_id.into()],
+        );
+        let buffer = save_to_buffer(&mut doc);
+        let result =
+            CompressDocument::compress_pdf_from_mem(&buffer, &CompressOptions::default()).unwrap();
+        let dict = image_dict(&result);
+        assert_eq!(Some(150), dict.get(b"Width").and_then(Object::as_i64).ok());
+        assert_eq!(
+            Some(b"DCTDecode".as_slice()),
+            dict.get(b"Filter").and_then(Object::as_name).ok()
+        );
+    }
+    #[test]
+    fn compress_pdf_from_mem_ok_6() {
+        // The image is drawn in a form scaled by half, 600 px over one inch is 600 DPI
+        let mut doc = create_document(1);
+        let image_id = add_gray_image(&mut doc, 600, 72);
+        let form_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "BBox" => vec![0.into(), 0.into(), 1.into(), 1.into()],
+                "Matrix" => vec![0.5.into(), 0.into(), 0.into(), 0.5.into(), 0.into(), 0.into()],
+                "Resources" => dictionary! { "XObject" => dictionary! { "Im1" => image_id } },
+            },
+            b"q 1 0 0 1 0 0 cm /Im1 Do Q".to_vec(),
+        ));
+        let page_id = doc.get_pages()[&1];
+        doc.get_dictionary_mut(page_id).unwrap().set(
+            "Resources",
+            dictionary! { "XObject" => dictionary! { "Fm1" => form_id } },
+        );
+        set_page_content(&mut doc, 1, b"q 144 0 0 144 100 100 cm /Fm1 Do Q");
+        let buffer = save_to_buffer(&mut doc);
+        let result =
+            CompressDocument::compress_pdf_from_mem(&buffer, &CompressOptions::default()).unwrap();
+        let dict = image_dict(&result);
+        assert_eq!(Some(150), dict.get(b"Width").and_then(Object::as_i64).ok());
+        assert_eq!(Some(150), dict.get(b"Height").and_then(Object::as_i64).ok());
+    }
+}
@@ -0,0 +1,404 @@
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+use std::io::Cursor;
+
+use super::{Error, Result};
+
+const A4_SIZE: (f32, f32) = (595.0, 842.0);
+const LETTER_SIZE: (f32, f32) = (612.0, 792.0);
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PageSize {
+    A4,
+    Letter,
+    // The page is the image size, one pixel is one point, plus the margins
+    Fit,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PageOrientation {
+    Portrait,
+    Landscape,
+    // Landscape for images wider than high
+    Auto,
+}
+
+pub struct ImagePageOptions {
+    pub page_size: PageSize,
+    pub orientation: PageOrientation,
+    // Space around the image in points
+    pub margin: f32,
+}
+
+impl Default for ImagePageOptions {
+    fn default() -> Self {
+        Self {
+            page_size: PageSize::A4,
+            orientation: PageOrientation::Auto,
+            margin: 0.0,
+        }
+    }
+}
+
+// An image ready to be drawn, `width` and `height` are the stored pixels before the orientation
+struct ImageXObject {
+    stream: Stream,
+    // Alpha channel, referred to by the image as its SMask
+    soft_mask: Option<Stream>,
+    width: f32,
+    height: f32,
+    orientation: Orientation,
+}
+
+pub struct ConvertDocument;
+
+impl ConvertDocument {
+    // Wrap every (filename, JPEG or PNG) image in a page of its own, scaled to fit inside the
+    // margins and centered. JPEG images are embedded as they are, the others are deflated.
+    pub fn images_to_pdf_from_mem(
+        images: &[(&str, &[u8])],
+        options: &ImagePageOptions,
+    ) -> Result<Document> {
+        if images.is_empty() {
+            return Err(Error::ImageListIsEmpty);
+        }
+        if !options.margin.is_finite() || options.margin < 0.0 {
+            return Err(Error::InValidImagePageOptions);
+        }
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let mut kids: Vec<Object> = Vec::new();
+        for (filename, buffer) in images {
+            let image = Self::_image_xobject(buffer)
+                .ok_or_else(|| Error::CannotReadImage(filename.to_string()))?;
+            let (display_width, display_height) = match image.orientation {
+                Orientation::Rotate90
+                | Orientation::Rotate270
+                | Orientation::Rotate90FlipH
+                | Orientation::Rotate270FlipH => (image.height, image.width),
+                _ => (image.width, image.height),
+            };
+            let (page_width, page_height) =
+                Self::_page_size(display_width, display_height, options);
+            let box_width = page_width - 2.0 * options.margin;
+            let box_height = page_height - 2.0 * options.margin;
+            if box_width <= 0.0 || box_height <= 0.0 {
+                return Err(Error::InValidImagePageOptions);
+            }
+            let scale = (box_width / display_width).min(box_height / display_height);
+            let (width, height) = (display_width * scale, display_height * scale);
+            let x = (page_width - width) / 2.0;
+            let y = (page_height - height) / 2.0;
+
+            let matrix = Self::_image_matrix(image.orientation, x, y, width, height);
+            let content = Content {
+                operations: vec![
+                    Operation::new("q", vec![]),
+                    Operation::new("cm", matrix.into_iter().map(Object::Real).collect()),
+                    Operation::new("Do", vec!["Im1".into()]),
+                    Operation::new("Q", vec![]),
+                ],
+            };
+            let mut image_stream = image.stream;
+            if let Some(soft_mask) = image.soft_mask {
+                image_stream.dict.set("SMask", doc.add_object(soft_mask));
+            }
+            let image_id = doc.add_object(image_stream);
+            let content_id = doc.add_object(Stream::new(
+                dictionary! {},
+                content.encode().map_err(Error::Lopdf)?,
+            ));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), page_width.into(), page_height.into()],
+                "Contents" => content_id,
+                "Resources" => dictionary! {
+                    "XObject" => dictionary! {
+                        "Im1" => image_id,
+                    },
+                },
+            });
+            kids.push(page_id.into());
+        }
+
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => kids.len() as i64,
+                "Kids" => kids,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.compress();
+
+        Ok(doc)
+    }
+
+    fn _page_size(
+        display_width: f32,
+        display_height: f32,
+        options: &ImagePageOptions,
+    ) -> (f32, f32) {
+        let (short_side, long_side) = match options.page_size {
+            PageSize::A4 => A4_SIZE,
+            PageSize::Letter => LETTER_SIZE,
+            PageSize::Fit => {
+                return (
+                    display_width + 2.0 * options.margin,
+                    display_height + 2.0 * options.margin,
+                )
+            }
+        };
+        let is_landscape = match options.orientation {
+            PageOrientation::Portrait => false,
+            PageOrientation::Landscape => true,
+            PageOrientation::Auto => display_width > display_height,
+        };
+        if is_landscape {
+            (long_side, short_side)
+        } else {
+            (short_side, long_side)
+        }
+    }
+
+    // The "cm" matrix drawing the image upright in the (x, y, width, height) box of the page.
+    // Images are drawn in the unit square, (0, 0) is the bottom left of the stored pixels.
+    fn _image_matrix(
+        orientation: Orientation,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) -> [f32; 6] {
+        // Stored (left to right, top to bottom) position to the upright one, both from 0 to 1
+        let upright = |s: f32, t: f32| match orientation {
+            Orientation::NoTransforms => (s, t),
+            Orientation::Rotate90 => (1.0 - t, s),
+            Orientation::Rotate180 => (1.0 - s, 1.0 - t),
+            Orientation::Rotate270 => (t, 1.0 - s),
+            Orientation::FlipHorizontal => (1.0 - s, t),
+            Orientation::FlipVertical => (s, 1.0 - t),
+            Orientation::Rotate90FlipH => (t, s),
+            Orientation::Rotate270FlipH => (1.0 - t, 1.0 - s),
+        };
+        // Image space (u, v) to the page, v goes up while t goes down
+        let page_point = |u: f32, v: f32| {
+            let (upright_x, upright_y) = upright(u, 1.0 - v);
+            (x + upright_x * width, y + (1.0 - upright_y) * height)
+        };
+        let (e, f) = page_point(0.0, 0.0);
+        let (u_x, u_y) = page_point(1.0, 0.0);
+        let (v_x, v_y) = page_point(0.0, 1.0);
+
+        [u_x - e, u_y - f, v_x - e, v_y - f, e, f]
+    }
+
+    fn _image_xobject(buffer: &[u8]) -> Option<ImageXObject> {
+        let format = image::guess_format(buffer).ok()?;
+        if format == ImageFormat::Jpeg {
+            if let Some(image) = Self::_jpeg_xobject(buffer) {
+                return Some(image);
+            }
+        }
+        if !matches!(format, ImageFormat::Jpeg | ImageFormat::Png) {
+            return None;
+        }
+
+        let mut decoder = ImageReader::with_format(Cursor::new(buffer), format)
+            .into_decoder()
+            .ok()?;
+        let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+        let mut image = DynamicImage::from_decoder(decoder).ok()?;
+        image.apply_orientation(orientation);
+        let (samples, color_space) = if image.color().has_color() {
+            (image.to_rgb8().into_raw(), "DeviceRGB")
+        } else {
+            (image.to_luma8().into_raw(), "DeviceGray")
+        };
+        let dict = dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => image.width() as i64,
+            "Height" => image.height() as i64,
+            "ColorSpace" => color_space,
+            "BitsPerComponent" => 8,
+        };
+        let soft_mask = image.color().has_alpha().then(|| {
+            let alpha: Vec<u8> = image.to_rgba8().pixels().map(|pixel| pixel[3]).collect();
+            Stream::new(
+                dictionary! {
+                    "Type" => "XObject",
+                    "Subtype" => "Image",
+                    "Width" => image.width() as i64,
+                    "Height" => image.height() as i64,
+                    "ColorSpace" => "DeviceGray",
+                    "BitsPerComponent" => 8,
+                },
+                alpha,
+            )
+        });
+
+        Some(ImageXObject {
+            stream: Stream::new(dict, samples),
+            soft_mask,
+            width: image.width() as f32,
+            height: image.height() as f32,
+            orientation: Orientation::NoTransforms,
+        })
+    }
+
+    // Gray and RGB JPEG images are embedded without decoding, the orientation is left to the page
+    fn _jpeg_xobject(buffer: &[u8]) -> Option<ImageXObject> {
+        let color_space = match Self::_jpeg_components(buffer)? {
+            1 => "DeviceGray",
+            3 => "DeviceRGB",
+            // CMYK JPEG images are often stored inverted, they are decoded to RGB instead
+            _ => return None,
+        };
+        let mut decoder = ImageReader::with_format(Cursor::new(buffer), ImageFormat::Jpeg)
+            .into_decoder()
+            .ok()?;
+        let (width, height) = decoder.dimensions();
+        let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+        let dict = dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => width as i64,
+            "Height" => height as i64,
+            "ColorSpace" => color_space,
+            "BitsPerComponent" => 8,
+            "Filter" => "DCTDecode",
+        };
+
+        Some(ImageXObject {
+            stream: Stream::new(dict, buffer.to_vec()),
+            soft_mask: None,
+            width: width as f32,
+            height: height as f32,
+            orientation,
+        })
+    }
+
+    // Number of color components in the frame header of a JPEG image
+    fn _jpeg_components(buffer: &[u8]) -> Option<u8> {
+        // Skip the SOI marker, every segment is FF, marker, 2 byte length including itself
+        let mut idx = 2;
+        while idx + 4 <= buffer.len() {
+            if buffer[idx] != 0xFF {
+                return None;
+            }
+            let marker = buffer[idx + 1];
+            let length = u16::from_be_bytes([buffer[idx + 2], buffer[idx + 3]]) as usize;
+            // SOF0 to SOF15 except DHT, JPG and DAC: precision, height, width, components
+            if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+                if buffer.get(idx + 4) != Some(&8) {
+                    return None;
+                }
+                return buffer.get(idx + 9).copied();
+            }
+            idx += 2 + length;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::save_to_buffer;
+    use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+    fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut buffer: Vec<u8> = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut buffer), format)
+            .unwrap();
+        buffer
+    }
+
+    fn media_box(doc: &Document, page_number: u32) -> Vec<f32> {
+        let page_id = doc.get_pages()[&page_number];
+        doc.get_dictionary(page_id)
+            .unwrap()
+            .get(b"MediaBox")
+            .and_then(Object::as_array)
+            .unwrap()
+            .iter()
+            .map(|value| value.as_float().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn images_to_pdf_from_mem_ok_1() {
+        // A wide JPEG on an A4 page turned landscape, a PNG with alpha on a portrait one
+        let jpeg = encode(
+            DynamicImage::ImageLuma8(GrayImage::from_pixel(400, 200, Luma([90]))),
+            ImageFormat::Jpeg,
+        );
+        let png = encode(
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(100, 300, Rgba([255, 0, 0, 128]))),
+            ImageFormat::Png,
+        );
+        let images: Vec<(&str, &[u8])> = vec![("wide.jpg", &jpeg), ("tall.png", &png)];
+        let mut doc =
+            ConvertDocument::images_to_pdf_from_mem(&images, &ImagePageOptions::default()).unwrap();
+        assert_eq!(vec![0.0, 0.0, 842.0, 595.0], media_box(&doc, 1));
+        assert_eq!(vec![0.0, 0.0, 595.0, 842.0], media_box(&doc, 2));
+        let streams: Vec<&Stream> = doc
+            .objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| stream.dict.has(b"Width"))
+            .collect();
+        // The JPEG is embedded as it is, the PNG has a soft mask
+        assert!(streams.iter().any(|stream| stream.content == jpeg));
+        assert_eq!(3, streams.len());
+        let buffer = save_to_buffer(&mut doc);
+        assert_eq!(2, Document::load_mem(&buffer).unwrap().get_pages().len());
+    }
+    #[test]
+    fn images_to_pdf_from_mem_ok_2() {
+        let png = encode(
+            DynamicImage::ImageLuma8(GrayImage::from_pixel(100, 50, Luma([0]))),
+            ImageFormat::Png,
+        );
+        let images: Vec<(&str, &[u8])> = vec![("photo.png", &png)];
+        let options = ImagePageOptions {
+            page_size: PageSize::Fit,
+            orientation: PageOrientation::Portrait,
+            margin: 10.0,
+        };
+        let doc = ConvertDocument::images_to_pdf_from_mem(&images, &options).unwrap();
+        assert_eq!(vec![0.0, 0.0, 120.0, 70.0], media_box(&doc, 1));
+
+        let options = ImagePageOptions {
+            margin: 300.0,
+            ..ImagePageOptions::default()
+        };
+        let result = ConvertDocument::images_to_pdf_from_mem(&images, &options);
+        assert!(matches!(result, Err(Error::InValidImagePageOptions)));
+        let images: Vec<(&str, &[u8])> = vec![("notes.txt", b"not an image")];
+        let result = ConvertDocument::images_to_pdf_from_mem(&images, &options);
+        assert!(matches!(result, Err(Error::CannotReadImage(filename)) if filename == "notes.txt"));
+    }
+    #[test]
+    fn image_matrix_ok_1() {
+        // Rotated 90 degrees clockwise, the bottom left of the stored pixels is the top left
+        let result =
+            ConvertDocument::_image_matrix(Orientation::Rotate90, 10.0, 20.0, 200.0, 100.0);
+        assert_eq!([0.0, -100.0, 200.0, 0.0, 10.0, 120.0], result);
+        let result =
+            ConvertDocument::_image_matrix(Orientation::NoTransforms, 10.0, 20.0, 200.0, 100.0);
+        assert_eq!([200.0, 0.0, 0.0, 100.0, 10.0, 20.0], result);
+    }
+}
@@ -0,0 +1,188 @@
+use lopdf::encryption::DecryptionError;
+use lopdf::xref::XrefEntry;
+use lopdf::{Document, Object, ObjectId, ObjectStream, Reader};
+
+use super::security_handler::SecurityHandler;
+use super::{Error, Result};
+
+// Object streams are encrypted as a whole, lopdf cannot parse them while loading an encrypted document
+// and drops their objects. Rename them while loading and parse them once decrypted.
+const ENCRYPTED_OBJECT_STREAM: &[u8] = b"EncryptedObjStm";
+
+pub struct DecryptDocument;
+
+impl DecryptDocument {
+    pub fn is_encrypted_from_mem(buffer: &[u8]) -> bool {
+        // Every encrypted document references its encryption dictionary with /Encrypt in the trailer
+        if !buffer.windows(8).any(|window| window == b"/Encrypt") {
+            return false;
+        }
+        match Document::load_mem(buffer) {
+            Ok(doc) => doc.is_encrypted(),
+            Err(_) => Self::_load_encrypted(buffer)
+                .map(|doc| doc.is_encrypted())
+                .unwrap_or(false),
+        }
+    }
+
+    // Load a document, decrypting it with the password when it is encrypted.
+    // The empty password opens documents protected with an owner password only.
+    pub fn load_mem(buffer: &[u8], password: &str) -> Result<Document> {
+        let error = match Document::load_mem(buffer) {
+            Ok(doc) if !doc.is_encrypted() => return Ok(doc),
+            Ok(_) => None,
+            Err(e) => Some(e),
+        };
+
+        let mut doc = Self::_load_encrypted(buffer)?;
+        if !doc.is_encrypted() {
+            return match error {
+                Some(e) => Err(Error::Lopdf(e)),
+                None => Ok(doc),
+            };
+        }
+
+        let encryption_id = doc.trailer.get(b"Encrypt").and_then(Object::as_reference);
+        let is_aes256 = doc.get_encrypted().is_ok_and(SecurityHandler::is_aes256);
+        match is_aes256 {
+            // lopdf only decrypts RC4
+            true => Self::_decrypt_aes256(&mut doc, password)?,
+            false => Self::_decrypt_rc4(&mut doc, password)?,
+        }
+        if let Ok(encryption_id) = encryption_id {
+            doc.objects.remove(&encryption_id);
+        }
+        Self::_restore_object_streams(&mut doc);
+
+        Ok(doc)
+    }
+
+    fn _decrypt_rc4(doc: &mut Document, password: &str) -> Result<()> {
+        doc.decrypt(password).map_err(|e| match e {
+            lopdf::Error::Decryption(DecryptionError::IncorrectPassword) => {
+                Error::EncryptedDocument
+            }
+            lopdf::Error::Decryption(DecryptionError::UnsupportedEncryption)
+            | lopdf::Error::Decryption(DecryptionError::InvalidKeyLength)
+            | lopdf::Error::Decryption(DecryptionError::InvalidRevision) => {
+                Error::UnsupportedEncryption
+            }
+            e => Error::Lopdf(e),
+        })
+    }
+
+    fn _decrypt_aes256(doc: &mut Document, password: &str) -> Result<()> {
+        let encrypt_dict = doc.get_encrypted().map_err(Error::Lopdf)?;
+        let file_key = SecurityHandler::file_key(encrypt_dict, password)?;
+        let is_metadata_encrypted = encrypt_dict
+            .get(b"EncryptMetadata")
+            .and_then(Object::as_bool)
+            .unwrap_or(true);
+        let encryption_id = doc.trailer.get(b"Encrypt").and_then(Object::as_reference);
+
+        for (object_id, object) in doc.objects.iter_mut() {
+            if encryption_id.as_ref().is_ok_and(|id| id == object_id) {
+                continue;
+            }
+            // Cross-reference streams are never encrypted, metadata may be left readable
+            let is_skipped = match object.type_name() {
+                Ok("XRef") => true,
+                Ok("Metadata") => !is_metadata_encrypted,
+                _ => false,
+            };
+            if !is_skipped {
+                SecurityHandler::decrypt_object(&file_key, object);
+            }
+        }
+        doc.trailer.remove(b"Encrypt");
+
+        Ok(())
+    }
+
+    fn _load_encrypted(buffer: &[u8]) -> Result<Document> {
+        let reader = Reader {
+            buffer,
+            document: Document::new(),
+        };
+
+        reader
+            .read(Some(Self::_rename_object_stream))
+            .map_err(Error::Lopdf)
+    }
+
+    fn _rename_object_stream(
+        object_id: ObjectId,
+        object: &mut Object,
+    ) -> Option<(ObjectId, Object)> {
+        if let Ok(stream) = object.as_stream_mut() {
+            if stream.dict.type_is(b"ObjStm") {
+                stream
+                    .dict
+                    .set("Type", Object::Name(ENCRYPTED_OBJECT_STREAM.to_vec()));
+            }
+        }
+
+        Some((object_id, object.clone()))
+    }
+
+    fn _restore_object_streams(doc: &mut Document) {
+        let stream_ids: Vec<ObjectId> = doc
+            .objects
+            .iter()
+            .filter(|(_, object)| {
+                object
+                    .as_stream()
+                    .map(|stream| stream.dict.type_is(ENCRYPTED_OBJECT_STREAM))
+                    .unwrap_or(false)
+            })
+            .map(|(object_id, _)| *object_id)
+            .collect();
+
+        for stream_id in stream_ids {
+            let Some(Object::Stream(mut stream)) = doc.objects.remove(&stream_id) else {
+                continue;
+            };
+            stream.dict.set("Type", "ObjStm");
+            let Ok(object_stream) = ObjectStream::new(&mut stream) else {
+                continue;
+            };
+            for (object_id, object) in object_stream.objects {
+                // Keep objects replaced by a later incremental update
+                let is_current = match doc.reference_table.get(object_id.0) {
+                    Some(XrefEntry::Compressed { container, .. }) => *container == stream_id.0,
+                    _ => false,
+                };
+                if is_current || !doc.objects.contains_key(&object_id) {
+                    doc.objects.insert(object_id, object);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{create_document, page_texts, save_to_buffer};
+
+    #[test]
+    fn is_encrypted_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(1));
+        let result = DecryptDocument::is_encrypted_from_mem(&buffer);
+        assert!(!result);
+    }
+    #[test]
+    fn load_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(2));
+        let result = DecryptDocument::load_mem(&buffer, "");
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 1", "Page 2"];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn load_mem_ok_2() {
+        let result = DecryptDocument::load_mem(b"not a pdf", "");
+        assert!(matches!(result, Err(Error::Lopdf(_))));
+    }
+}
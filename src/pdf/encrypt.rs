@@ -0,0 +1,185 @@
+use lopdf::{Document, Object, StringFormat};
+use md5::{Digest, Md5};
+
+use super::security_handler::SecurityHandler;
+use super::{DecryptDocument, Error, Result};
+
+// Bits 7, 8 and 13-32 are reserved and must be set (PDF 1.7, Table 22)
+const PERMISSION_RESERVED: u32 = 0xFFFF_F0C0;
+// Print and high quality print
+const PERMISSION_PRINT: u32 = (1 << 2) | (1 << 11);
+// Modify, annotate, fill forms and assemble
+const PERMISSION_MODIFY: u32 = (1 << 3) | (1 << 5) | (1 << 8) | (1 << 10);
+// Copy and extract for accessibility
+const PERMISSION_COPY: u32 = (1 << 4) | (1 << 9);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Permissions {
+    pub is_print: bool,
+    pub is_copy: bool,
+    pub is_modify: bool,
+}
+
+impl Permissions {
+    // The /P entry of the encryption dictionary, a 32-bit signed integer
+    fn to_flags(self) -> i32 {
+        let mut flags = PERMISSION_RESERVED;
+        if self.is_print {
+            flags |= PERMISSION_PRINT;
+        }
+        if self.is_modify {
+            flags |= PERMISSION_MODIFY;
+        }
+        if self.is_copy {
+            flags |= PERMISSION_COPY;
+        }
+
+        flags as i32
+    }
+}
+
+pub struct EncryptDocument;
+
+impl EncryptDocument {
+    // Encrypt a document with AES-256 (standard security handler, revision 6).
+    // An empty user password opens the document without a prompt but keeps the permissions,
+    // an empty owner password falls back to the user password.
+    pub fn encrypt_pdf_from_mem(
+        buffer: &[u8],
+        user_password: &str,
+        owner_password: &str,
+        permissions: Permissions,
+    ) -> Result<Document> {
+        if user_password.is_empty() && owner_password.is_empty() {
+            return Err(Error::PasswordIsEmpty);
+        }
+        let owner_password = if owner_password.is_empty() {
+            user_password
+        } else {
+            owner_password
+        };
+
+        let mut doc = DecryptDocument::load_mem(buffer, "")?;
+        Self::_set_file_id(&mut doc, buffer);
+        let flags = permissions.to_flags();
+        let (file_key, encrypt_dict) =
+            SecurityHandler::create(user_password, owner_password, flags)?;
+
+        for object in doc.objects.values_mut() {
+            // Skipped by the writer, see lopdf::Document::save_to
+            let is_skipped = object
+                .type_name()
+                .map(|name| ["ObjStm", "XRef", "Linearized"].contains(&name))
+                .unwrap_or(false);
+            if !is_skipped {
+                SecurityHandler::encrypt_object(&file_key, object)?;
+            }
+        }
+
+        let encrypt_id = doc.add_object(encrypt_dict);
+        doc.trailer.set("Encrypt", encrypt_id);
+        // AES-256 needs PDF 2.0
+        if doc.version.as_str() < "2.0" {
+            doc.version = "2.0".to_string();
+        }
+
+        Ok(doc)
+    }
+
+    // Encrypted documents need a file identifier, create it from the file content when missing
+    fn _set_file_id(doc: &mut Document, buffer: &[u8]) {
+        if doc.trailer.has(b"ID") {
+            return;
+        }
+
+        let file_id = Md5::digest(buffer).to_vec();
+        doc.trailer.set(
+            "ID",
+            vec![
+                Object::String(file_id.clone(), StringFormat::Hexadecimal),
+                Object::String(file_id, StringFormat::Hexadecimal),
+            ],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{create_document, page_texts, save_to_buffer};
+
+    const ALL_PERMISSIONS: Permissions = Permissions {
+        is_print: true,
+        is_copy: true,
+        is_modify: true,
+    };
+
+    #[test]
+    fn encrypt_pdf_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(2));
+        let mut doc =
+            EncryptDocument::encrypt_pdf_from_mem(&buffer, "user", "owner", ALL_PERMISSIONS)
+                .unwrap();
+        let buffer = save_to_buffer(&mut doc);
+        assert!(DecryptDocument::is_encrypted_from_mem(&buffer));
+        let result = DecryptDocument::load_mem(&buffer, "user");
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 1", "Page 2"];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn encrypt_pdf_from_mem_ok_2() {
+        let buffer = save_to_buffer(&mut create_document(1));
+        let mut doc =
+            EncryptDocument::encrypt_pdf_from_mem(&buffer, "user", "owner", ALL_PERMISSIONS)
+                .unwrap();
+        let buffer = save_to_buffer(&mut doc);
+        let result = DecryptDocument::load_mem(&buffer, "wrong");
+        assert!(matches!(result, Err(Error::EncryptedDocument)));
+        let result = DecryptDocument::load_mem(&buffer, "");
+        assert!(matches!(result, Err(Error::EncryptedDocument)));
+    }
+    #[test]
+    fn encrypt_pdf_from_mem_ok_3() {
+        // Owner password only, opens without a password
+        let buffer = save_to_buffer(&mut create_document(1));
+        let permissions = Permissions {
+            is_print: true,
+            is_copy: false,
+            is_modify: false,
+        };
+        let mut doc =
+            EncryptDocument::encrypt_pdf_from_mem(&buffer, "", "owner", permissions).unwrap();
+        let flags = doc
+            .get_encrypted()
+            .and_then(|dict| dict.get(b"P"))
+            .and_then(Object::as_i64)
+            .unwrap();
+        assert_eq!(PERMISSION_RESERVED | PERMISSION_PRINT, flags as u32);
+        let buffer = save_to_buffer(&mut doc);
+        let result = DecryptDocument::load_mem(&buffer, "");
+        assert_eq!(vec!["Page 1"], page_texts(&result.unwrap()));
+    }
+    #[test]
+    fn encrypt_pdf_from_mem_ok_4() {
+        let buffer = save_to_buffer(&mut create_document(1));
+        let result = EncryptDocument::encrypt_pdf_from_mem(&buffer, "", "", ALL_PERMISSIONS);
+        assert!(matches!(result, Err(Error::PasswordIsEmpty)));
+    }
+    #[test]
+    fn encrypt_pdf_from_mem_ok_5() {
+        let buffer = save_to_buffer(&mut create_document(1));
+        let mut doc =
+            EncryptDocument::encrypt_pdf_from_mem(&buffer, "user", "owner", ALL_PERMISSIONS)
+                .unwrap();
+        let encrypt_dict = doc.get_encrypted().unwrap();
+        assert_eq!(5, encrypt_dict.get(b"V").unwrap().as_i64().unwrap());
+        assert_eq!(6, encrypt_dict.get(b"R").unwrap().as_i64().unwrap());
+        // The page content is not readable without the password
+        let buffer = save_to_buffer(&mut doc);
+        assert!(!buffer.windows(6).any(|window| window == b"Page 1"));
+        let result = DecryptDocument::load_mem(&buffer, "owner");
+        assert_eq!(vec!["Page 1"], page_texts(&result.unwrap()));
+    }
+}
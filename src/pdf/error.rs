@@ -1,3 +1,5 @@
+use super::page_range::PageRangeError;
+
 // region:    --- Error
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -6,16 +8,50 @@ pub enum Error {
     // Lopdf Error from lopdf crate
     Lopdf(lopdf::Error),
 
+    // Error for compress::CompressDocument
+    InValidCompressOptions,
+
+    // Error for convert::ConvertDocument
+    ImageListIsEmpty,
+    CannotReadImage(String),
+    InValidImagePageOptions,
+
+    // Error for decrypt::DecryptDocument
+    EncryptedDocument,
+    UnsupportedEncryption,
+
+    // Error for encrypt::EncryptDocument
+    PasswordIsEmpty,
+    RandomBytesUnavailable,
+
     // Error for merge::Merge
     PageObjectNotFound,
     CatalogObjectNotFound,
+    InValidMergePageNumbers(String),
+    InterleaveNeedsTwoDocuments,
+
+    // Error for organize::OrganizeDocument
+    CannotRemoveAllPages,
+
+    // Error for rotate::RotateDocument
+    InValidRotation,
+
+    // Error for thumbnail::ThumbnailDocument
+    CannotRenderDocument,
+    InValidRenderOptions,
 
     // Error For split::Split
     SplitPagesStrIsEmpty,
     CannotCreateRegex,
     InValidPageNumbers,
+    InValidPageRange(PageRangeError),
     PageNumberOverFlow,
     FiexedPageNumberOverFlow,
+    OutlineLevelNotFound,
+    PagesOverSizeLimit(Vec<u32>),
+    BlankPageNotFound,
+    TextPatternNotFound,
+    PickedPagesOverlap(Vec<u32>),
 }
 // endregion: --- Error
 
@@ -1,56 +1,147 @@
-use lopdf::{Document, Object, ObjectId};
-use std::collections::BTreeMap;
+use lopdf::{decode_text_string, text_string, Dictionary, Document, Object, ObjectId};
+use std::collections::{BTreeMap, HashSet};
+
+use super::{DecryptDocument, Error, OrganizeDocument, OutlineItem, Result};
+
+#[derive(Debug, Clone)]
+pub struct MergeInput<'a> {
+    pub filename: String,
+    pub buffer: &'a [u8],
+    // Title of the bookmark created for this input, the file name is used when not set
+    pub label: Option<String>,
+    // Pages to take from this input using the split syntax, e.g. "1-3, 7", all pages when not set
+    pub page_range: Option<String>,
+}
+
+impl<'a> MergeInput<'a> {
+    pub fn new(filename: &str, buffer: &'a [u8]) -> Self {
+        Self {
+            filename: filename.to_string(),
+            buffer,
+            label: None,
+            page_range: None,
+        }
+    }
+
+    fn outline_title(&self) -> String {
+        match &self.label {
+            Some(label) if !label.trim().is_empty() => label.trim().to_string(),
+            _ => MergeDocument::_outline_title(&self.filename),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum MergeMode {
+    // Append the pages of every input one after the other
+    #[default]
+    Append,
+    // Alternate the pages of two inputs (A1, B1, A2, B2, ...), e.g. odd and even pages of a duplex scan
+    Interleave {
+        is_reverse_second: bool,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeOptions {
+    pub mode: MergeMode,
+    // Nest the outline of every input under a top-level bookmark named after the input file
+    pub is_nest_outlines: bool,
+    // Add a top-level bookmark pointing to the first page of every input
+    pub is_bookmark_per_input: bool,
+}
 
-use super::{Error, Result};
 pub struct MergeDocument;
 
 impl MergeDocument {
-    pub fn merge_pdf_from_mem(buffers: Vec<&[u8]>) -> Result<Document> {
-        let documents: Result<Vec<Document>> = buffers
+    pub fn merge_pdf_from_mem(inputs: Vec<MergeInput>, options: &MergeOptions) -> Result<Document> {
+        let documents: Result<Vec<(Document, String)>> = inputs
             .iter()
-            .map(|buffer| Document::load_mem(*buffer).map_err(|e| Error::Lopdf(e)))
+            .map(|input| {
+                let mut doc = DecryptDocument::load_mem(input.buffer, "")?;
+                if let Some(page_range) = &input.page_range {
+                    Self::_select_pages(&mut doc, page_range)
+                        .map_err(|_e| Error::InValidMergePageNumbers(input.filename.clone()))?;
+                }
+                Ok((doc, input.outline_title()))
+            })
             .collect();
 
-        Self::_merge_pdf_from_documents(documents?)
+        Self::_merge_pdf_from_documents(documents?, options)
     }
 
-    fn _merge_pdf_from_file_paths(pdfs: Vec<&str>) -> Result<Document> {
-        let documents: Result<Vec<Document>> = pdfs
+    fn _merge_pdf_from_file_paths(pdfs: Vec<&str>, options: &MergeOptions) -> Result<Document> {
+        let documents: Result<Vec<(Document, String)>> = pdfs
             .iter()
             .map(|pdf| {
-                let doc = Document::load(*pdf).map_err(|e| Error::Lopdf(e))?;
-                Ok(doc)
+                let doc = Document::load(pdf).map_err(Error::Lopdf)?;
+                let filename = std::path::Path::new(pdf)
+                    .file_name()
+                    .map(|filename| filename.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                Ok((doc, Self::_outline_title(&filename)))
             })
             .collect();
 
-        Self::_merge_pdf_from_documents(documents?)
+        Self::_merge_pdf_from_documents(documents?, options)
     }
 
-    fn _merge_pdf_from_documents(documents: Vec<Document>) -> Result<Document> {
+    fn _merge_pdf_from_documents(
+        documents: Vec<(Document, String)>,
+        options: &MergeOptions,
+    ) -> Result<Document> {
         // Define a starting `max_id` (will be used as start index for object_ids).
         let mut max_id = 1;
-        // Collect all Documents Objects grouped by a map
-        let mut documents_pages: BTreeMap<ObjectId, Object> = BTreeMap::new();
+        // Collect all Documents Objects grouped by a map, and the pages of every Document in page order
+        let mut documents_pages: Vec<Vec<(ObjectId, Object)>> = Vec::new();
         let mut documents_objects: BTreeMap<ObjectId, Object> = BTreeMap::new();
+        // Collect all Documents Outlines, already pointing to the renumbered pages
+        let mut documents_outlines: Vec<OutlineItem> = Vec::new();
+        // Collect all Documents form fields into one "AcroForm"
+        let mut documents_acro_form: Option<Dictionary> = None;
+        let mut documents_field_names: HashSet<String> = HashSet::new();
 
-        for mut doc in documents {
+        for (mut doc, title) in documents {
             doc.renumber_objects_with(max_id);
             max_id = doc.max_id + 1;
+            Self::_merge_acro_form(
+                &mut documents_acro_form,
+                &mut doc,
+                &mut documents_field_names,
+            );
             let pages = Self::_collect_pages(&mut doc)?;
-            documents_pages.extend(pages);
-            documents_objects.extend(doc.objects.clone());
+            let outlines = OutlineItem::read_from_document(&doc);
+            let first_page_id = doc.page_iter().next();
+            if options.is_nest_outlines && !outlines.is_empty() {
+                documents_outlines.push(OutlineItem::new(&title, first_page_id, outlines));
+            } else {
+                if options.is_bookmark_per_input {
+                    documents_outlines.push(OutlineItem::new(&title, first_page_id, Vec::new()));
+                }
+                documents_outlines.extend(outlines);
+            }
+            documents_pages.push(pages);
+            documents_objects.extend(doc.objects);
         }
+        let documents_pages = Self::_arrange_pages(documents_pages, options.mode)?;
 
         // "Catalog" and "Pages" are mandatory.
         // Recreate the main Document with "Catalog" and "Pages" objects.
-        let document = Self::_recreate_objects_pages(&documents_objects, &documents_pages)?;
+        let document = Self::_recreate_objects_pages(
+            &documents_objects,
+            &documents_pages,
+            &documents_outlines,
+            documents_acro_form,
+        )?;
 
         Ok(document)
     }
 
     fn _recreate_objects_pages(
         documents_objects: &BTreeMap<ObjectId, Object>,
-        documents_pages: &BTreeMap<ObjectId, Object>,
+        documents_pages: &[(ObjectId, Object)],
+        documents_outlines: &[OutlineItem],
+        documents_acro_form: Option<Dictionary>,
     ) -> Result<Document> {
         let mut document = Document::with_version("1.5");
 
@@ -60,7 +151,8 @@ impl MergeDocument {
 
         // Process all objects except "Page" type
         for (object_id, object) in documents_objects.iter() {
-            // We have to ignore "Page" (as are processed later), "Outlines" and "Outline" objects.
+            // We have to ignore "Page" (as are processed later), "Outlines" and "Outline" objects
+            // (rebuilt from the collected outlines later).
             // All other objects should be collected and inserted into the main Document.
             match object.type_name().unwrap_or("") {
                 "Catalog" => {
@@ -101,8 +193,8 @@ impl MergeDocument {
                     }
                 }
                 "Page" => {}     // Ignored, processed later and separately
-                "Outlines" => {} // Ignored, not supported yet
-                "Outline" => {}  // Ignored, not supported yet
+                "Outlines" => {} // Ignored, rebuilt from bookmarks
+                "Outline" => {}  // Ignored, rebuilt from bookmarks
                 _ => {
                     document.objects.insert(*object_id, object.clone());
                 }
@@ -142,7 +234,7 @@ impl MergeDocument {
                 dictionary.set(
                     "Kids",
                     documents_pages
-                        .into_iter()
+                        .iter()
                         .map(|(object_id, _)| Object::Reference(*object_id))
                         .collect::<Vec<_>>(),
                 );
@@ -161,7 +253,10 @@ impl MergeDocument {
             Ok(dictionary) => {
                 let mut dictionary = dictionary.clone();
                 dictionary.set("Pages", pages_object.0);
-                dictionary.remove(b"Outlines"); // Outlines not supported in merged PDFs
+                dictionary.remove(b"Outlines"); // Outlines are rebuilt from bookmarks
+                if let Some(acro_form) = documents_acro_form {
+                    dictionary.set("AcroForm", acro_form);
+                }
 
                 document
                     .objects
@@ -177,38 +272,588 @@ impl MergeDocument {
         // Update the max internal ID as wasn't updated before due to direct objects insertion
         document.max_id = document.objects.len() as u32;
 
+        // Add the collected outlines as bookmarks, their pages are renumbered together with the objects
+        OutlineItem::add_bookmarks(&mut document, documents_outlines, None);
+
         // Reorder all new Document objects
         document.renumber_objects();
 
-        // Set any Bookmarks to the First child if they are not set to a page
-        document.adjust_zero_pages();
-
         // Set all bookmarks to the PDF Object tree then set the Outlines to the Bookmark content map.
-        if let Some(n) = document.build_outline() {
-            if let Ok(x) = document.get_object_mut(catalog_object.0) {
-                if let Object::Dictionary(ref mut dict) = x {
-                    dict.set("Outlines", Object::Reference(n));
-                }
-            }
-        }
+        OutlineItem::build_outline(&mut document);
 
         document.compress();
         Ok(document)
     }
 
-    fn _collect_pages(doc: &mut Document) -> Result<BTreeMap<ObjectId, Object>> {
-        let contents: Result<BTreeMap<ObjectId, Object>> = doc
+    fn _select_pages(doc: &mut Document, page_range: &str) -> Result<()> {
+        if page_range.trim().is_empty() {
+            return Ok(());
+        }
+
+        OrganizeDocument::extract_pages(doc, page_range)
+    }
+
+    fn _arrange_pages(
+        documents_pages: Vec<Vec<(ObjectId, Object)>>,
+        mode: MergeMode,
+    ) -> Result<Vec<(ObjectId, Object)>> {
+        match mode {
+            MergeMode::Append => Ok(documents_pages.into_iter().flatten().collect()),
+            MergeMode::Interleave { is_reverse_second } => {
+                let [first_pages, mut second_pages]: [Vec<(ObjectId, Object)>; 2] = documents_pages
+                    .try_into()
+                    .map_err(|_e| Error::InterleaveNeedsTwoDocuments)?;
+                if is_reverse_second {
+                    second_pages.reverse();
+                }
+
+                // The remaining pages of the longer input are appended at the end
+                let mut pages: Vec<(ObjectId, Object)> = Vec::new();
+                let mut first_pages = first_pages.into_iter();
+                let mut second_pages = second_pages.into_iter();
+                loop {
+                    match (first_pages.next(), second_pages.next()) {
+                        (None, None) => break,
+                        (first_page, second_page) => {
+                            pages.extend(first_page);
+                            pages.extend(second_page);
+                        }
+                    }
+                }
+
+                Ok(pages)
+            }
+        }
+    }
+
+    fn _collect_pages(doc: &mut Document) -> Result<Vec<(ObjectId, Object)>> {
+        let contents: Result<Vec<(ObjectId, Object)>> = doc
             .get_pages()
-            .into_iter()
-            .map(|(_, object_id)| {
-                let obj = doc
-                    .get_object(object_id)
-                    .map_err(|e| Error::Lopdf(e))
-                    .cloned()?;
+            .into_values()
+            .map(|object_id| {
+                let obj = doc.get_object(object_id).map_err(Error::Lopdf).cloned()?;
                 Ok((object_id, obj))
             })
             .collect();
 
         contents
     }
+
+    fn _merge_acro_form(
+        acro_form: &mut Option<Dictionary>,
+        doc: &mut Document,
+        field_names: &mut HashSet<String>,
+    ) {
+        let Some(doc_acro_form) = doc
+            .catalog()
+            .and_then(|catalog| catalog.get(b"AcroForm"))
+            .ok()
+            .and_then(|doc_acro_form| Self::_resolve_dictionary(doc, doc_acro_form))
+        else {
+            return;
+        };
+        let field_ids: Vec<ObjectId> = doc_acro_form
+            .get(b"Fields")
+            .and_then(|fields| doc.dereference(fields))
+            .and_then(|(_, fields)| fields.as_array())
+            .map(|fields| {
+                fields
+                    .iter()
+                    .filter_map(|field| field.as_reference().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Fully qualified names start with the top-level field name,
+        // renaming a colliding top-level field keeps its whole subtree apart.
+        // A new name must not take the name of another field of the document either
+        let doc_original_names: HashSet<String> = field_ids
+            .iter()
+            .filter_map(|field_id| doc.get_dictionary(*field_id).ok())
+            .filter_map(|field| field.get(b"T").and_then(decode_text_string).ok())
+            .collect();
+        let mut doc_field_names: HashSet<String> = HashSet::new();
+        for field_id in &field_ids {
+            let Ok(field) = doc.get_dictionary_mut(*field_id) else {
+                continue;
+            };
+            let Ok(name) = field.get(b"T").and_then(decode_text_string) else {
+                continue;
+            };
+            let mut new_name = name.clone();
+            let mut suffix = 2;
+            while field_names.contains(&new_name)
+                || doc_field_names.contains(&new_name)
+                || (new_name != name && doc_original_names.contains(&new_name))
+            {
+                new_name = format!("{name}_{suffix}");
+                suffix += 1;
+            }
+            if new_name != name {
+                field.set("T", text_string(&new_name));
+            }
+            doc_field_names.insert(new_name);
+        }
+        field_names.extend(doc_field_names);
+
+        let fields: Vec<Object> = field_ids.into_iter().map(Object::Reference).collect();
+        match acro_form {
+            None => {
+                let mut new_acro_form = doc_acro_form.clone();
+                new_acro_form.set("Fields", fields);
+                // XFA forms describe the fields of one document only, let viewers use the AcroForm
+                new_acro_form.remove(b"XFA");
+                if let Some(resources) = Self::_resolve_default_resources(doc, &doc_acro_form) {
+                    new_acro_form.set("DR", resources);
+                }
+                *acro_form = Some(new_acro_form);
+            }
+            Some(acro_form) => {
+                if let Ok(Object::Array(ref mut acro_form_fields)) = acro_form.get_mut(b"Fields") {
+                    acro_form_fields.extend(fields);
+                }
+                if let Ok(true) = doc_acro_form
+                    .get(b"NeedAppearances")
+                    .and_then(Object::as_bool)
+                {
+                    acro_form.set("NeedAppearances", true);
+                }
+                let sig_flags =
+                    |form: &Dictionary| form.get(b"SigFlags").and_then(Object::as_i64).unwrap_or(0);
+                if sig_flags(&doc_acro_form) > sig_flags(acro_form) {
+                    acro_form.set("SigFlags", sig_flags(&doc_acro_form));
+                }
+                if !acro_form.has(b"DA") {
+                    if let Ok(default_appearance) = doc_acro_form.get(b"DA") {
+                        acro_form.set("DA", default_appearance.clone());
+                    }
+                }
+                Self::_merge_default_resources(acro_form, doc, &doc_acro_form);
+            }
+        }
+    }
+
+    fn _merge_default_resources(
+        acro_form: &mut Dictionary,
+        doc: &Document,
+        doc_acro_form: &Dictionary,
+    ) {
+        let Some(doc_resources) = Self::_resolve_default_resources(doc, doc_acro_form) else {
+            return;
+        };
+        let mut resources = acro_form
+            .get(b"DR")
+            .and_then(Object::as_dict)
+            .cloned()
+            .unwrap_or_default();
+        // Keep the resources of the first documents when names collide, e.g. /Helv
+        for (key, doc_resource) in doc_resources.iter() {
+            match (
+                resources.get(key).and_then(Object::as_dict),
+                doc_resource.as_dict(),
+            ) {
+                (Ok(resource), Ok(doc_resource)) => {
+                    let mut resource = resource.clone();
+                    for (name, value) in doc_resource.iter() {
+                        if !resource.has(name) {
+                            resource.set(name.clone(), value.clone());
+                        }
+                    }
+                    resources.set(key.clone(), resource);
+                }
+                _ => {
+                    if !resources.has(key) {
+                        resources.set(key.clone(), doc_resource.clone());
+                    }
+                }
+            }
+        }
+        acro_form.set("DR", resources);
+    }
+
+    fn _resolve_default_resources(
+        doc: &Document,
+        doc_acro_form: &Dictionary,
+    ) -> Option<Dictionary> {
+        let mut resources = Self::_resolve_dictionary(doc, doc_acro_form.get(b"DR").ok()?)?;
+        for (_, resource) in resources.iter_mut() {
+            if let Some(resource_dictionary) = Self::_resolve_dictionary(doc, resource) {
+                *resource = Object::Dictionary(resource_dictionary);
+            }
+        }
+
+        Some(resources)
+    }
+
+    fn _resolve_dictionary(doc: &Document, object: &Object) -> Option<Dictionary> {
+        doc.dereference(object)
+            .ok()
+            .and_then(|(_, object)| object.as_dict().ok())
+            .cloned()
+    }
+
+    fn _outline_title(filename: &str) -> String {
+        filename
+            .strip_suffix(".pdf")
+            .or_else(|| filename.strip_suffix(".PDF"))
+            .unwrap_or(filename)
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{
+        add_outline, add_text_field, create_document, page_texts, save_to_buffer,
+    };
+
+    fn outline_titles(outlines: &[OutlineItem]) -> Vec<String> {
+        outlines
+            .iter()
+            .map(|outline| outline.title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn merge_pdf_from_mem_ok_1() {
+        let buffer_a = save_to_buffer(&mut create_document(2));
+        let buffer_b = save_to_buffer(&mut create_document(3));
+        let inputs = vec![
+            MergeInput::new("a.pdf", &buffer_a),
+            MergeInput::new("b.pdf", &buffer_b),
+        ];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 1", "Page 2", "Page 1", "Page 2", "Page 3"];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn merge_pdf_from_mem_outlines_ok_1() {
+        let mut doc_a = create_document(2);
+        add_outline(&mut doc_a, &[("Intro", 1), ("Body", 2)]);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let mut doc_b = create_document(2);
+        add_outline(&mut doc_b, &[("Summary", 2)]);
+        let buffer_b = save_to_buffer(&mut doc_b);
+        let inputs = vec![
+            MergeInput::new("a.pdf", &buffer_a),
+            MergeInput::new("b.pdf", &buffer_b),
+        ];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        let outlines = OutlineItem::read_from_document(&result);
+        assert_eq!(vec!["Intro", "Body", "Summary"], outline_titles(&outlines));
+        let pages = result.get_pages();
+        assert_eq!(Some(pages[&1]), outlines[0].page_id);
+        assert_eq!(Some(pages[&2]), outlines[1].page_id);
+        assert_eq!(Some(pages[&4]), outlines[2].page_id);
+    }
+    #[test]
+    fn merge_pdf_from_mem_outlines_ok_2() {
+        let mut doc_a = create_document(2);
+        add_outline(&mut doc_a, &[("Intro", 1), ("Body", 2)]);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let buffer_b = save_to_buffer(&mut create_document(2));
+        let inputs = vec![
+            MergeInput::new("report.pdf", &buffer_a),
+            MergeInput::new("appendix.pdf", &buffer_b),
+        ];
+        let options = MergeOptions {
+            is_nest_outlines: true,
+            ..Default::default()
+        };
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &options);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        let outlines = OutlineItem::read_from_document(&result);
+        assert_eq!(vec!["report"], outline_titles(&outlines));
+        assert_eq!(vec!["Intro", "Body"], outline_titles(&outlines[0].children));
+        assert_eq!(Some(result.get_pages()[&1]), outlines[0].page_id);
+    }
+    #[test]
+    fn merge_pdf_from_mem_outlines_ok_3() {
+        let mut doc_a = create_document(1);
+        add_outline(&mut doc_a, &[("บทนำ", 1)]);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let inputs = vec![MergeInput::new("a.pdf", &buffer_a)];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(result.is_ok());
+        let outlines = OutlineItem::read_from_document(&result.unwrap());
+        assert_eq!(vec!["บทนำ"], outline_titles(&outlines));
+    }
+    #[test]
+    fn merge_pdf_from_mem_bookmark_per_input_ok_1() {
+        let buffer_a = save_to_buffer(&mut create_document(2));
+        let buffer_b = save_to_buffer(&mut create_document(3));
+        let buffer_c = save_to_buffer(&mut create_document(1));
+        let inputs = vec![
+            MergeInput::new("invoice-001.pdf", &buffer_a),
+            MergeInput {
+                label: Some("Second invoice".to_string()),
+                ..MergeInput::new("invoice-002.pdf", &buffer_b)
+            },
+            MergeInput::new("invoice-003.pdf", &buffer_c),
+        ];
+        let options = MergeOptions {
+            is_bookmark_per_input: true,
+            ..Default::default()
+        };
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &options);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        let outlines = OutlineItem::read_from_document(&result);
+        let expected = vec!["invoice-001", "Second invoice", "invoice-003"];
+        assert_eq!(expected, outline_titles(&outlines));
+        let pages = result.get_pages();
+        let result: Vec<Option<ObjectId>> = outlines.iter().map(|o| o.page_id).collect();
+        let expected = vec![Some(pages[&1]), Some(pages[&3]), Some(pages[&6])];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn merge_pdf_from_mem_bookmark_per_input_ok_2() {
+        let mut doc_a = create_document(2);
+        add_outline(&mut doc_a, &[("Intro", 1), ("Body", 2)]);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let buffer_b = save_to_buffer(&mut create_document(1));
+        let inputs = vec![
+            MergeInput::new("a.pdf", &buffer_a),
+            MergeInput::new("b.pdf", &buffer_b),
+        ];
+        let options = MergeOptions {
+            is_bookmark_per_input: true,
+            ..Default::default()
+        };
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &options);
+        assert!(result.is_ok());
+        let outlines = OutlineItem::read_from_document(&result.unwrap());
+        let expected = vec!["a", "Intro", "Body", "b"];
+        assert_eq!(expected, outline_titles(&outlines));
+    }
+    #[test]
+    fn merge_pdf_from_mem_page_range_ok_1() {
+        let buffer_a = save_to_buffer(&mut create_document(5));
+        let buffer_b = save_to_buffer(&mut create_document(2));
+        let buffer_c = save_to_buffer(&mut create_document(8));
+        let inputs = vec![
+            MergeInput {
+                page_range: Some("1-3".to_string()),
+                ..MergeInput::new("a.pdf", &buffer_a)
+            },
+            MergeInput::new("b.pdf", &buffer_b),
+            MergeInput {
+                page_range: Some("7".to_string()),
+                ..MergeInput::new("c.pdf", &buffer_c)
+            },
+        ];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 1", "Page 2", "Page 3", "Page 1", "Page 2", "Page 7"];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn merge_pdf_from_mem_page_range_ok_2() {
+        let mut doc_a = create_document(3);
+        add_outline(&mut doc_a, &[("Intro", 1), ("Body", 2), ("End", 3)]);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let inputs = vec![MergeInput {
+            page_range: Some("2-3".to_string()),
+            ..MergeInput::new("a.pdf", &buffer_a)
+        }];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(result.is_ok());
+        let outlines = OutlineItem::read_from_document(&result.unwrap());
+        assert_eq!(vec!["Body", "End"], outline_titles(&outlines));
+    }
+    #[test]
+    fn merge_pdf_from_mem_page_range_ok_3() {
+        let buffer_a = save_to_buffer(&mut create_document(2));
+        let inputs = vec![MergeInput {
+            page_range: Some("1-3".to_string()),
+            ..MergeInput::new("a.pdf", &buffer_a)
+        }];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(
+            matches!(result, Err(Error::InValidMergePageNumbers(filename)) if filename == "a.pdf")
+        );
+    }
+    #[test]
+    fn merge_pdf_from_mem_interleave_ok_1() {
+        let buffer_a = save_to_buffer(&mut create_document(3));
+        let buffer_b = save_to_buffer(&mut create_document(3));
+        let inputs = vec![
+            MergeInput::new("odd.pdf", &buffer_a),
+            MergeInput::new("even.pdf", &buffer_b),
+        ];
+        let options = MergeOptions {
+            mode: MergeMode::Interleave {
+                is_reverse_second: true,
+            },
+            ..Default::default()
+        };
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &options);
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 1", "Page 3", "Page 2", "Page 2", "Page 3", "Page 1"];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn merge_pdf_from_mem_interleave_ok_2() {
+        let buffer_a = save_to_buffer(&mut create_document(3));
+        let buffer_b = save_to_buffer(&mut create_document(1));
+        let inputs = vec![
+            MergeInput::new("a.pdf", &buffer_a),
+            MergeInput::new("b.pdf", &buffer_b),
+        ];
+        let options = MergeOptions {
+            mode: MergeMode::Interleave {
+                is_reverse_second: false,
+            },
+            ..Default::default()
+        };
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &options);
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 1", "Page 1", "Page 2", "Page 3"];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn merge_pdf_from_mem_interleave_ok_3() {
+        let buffer_a = save_to_buffer(&mut create_document(1));
+        let inputs = vec![MergeInput::new("a.pdf", &buffer_a)];
+        let options = MergeOptions {
+            mode: MergeMode::Interleave {
+                is_reverse_second: false,
+            },
+            ..Default::default()
+        };
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &options);
+        assert!(matches!(result, Err(Error::InterleaveNeedsTwoDocuments)));
+    }
+    #[test]
+    fn merge_pdf_from_mem_acro_form_ok_1() {
+        let mut doc_a = create_document(1);
+        add_text_field(&mut doc_a, "applicant", "Alice", 1);
+        add_text_field(&mut doc_a, "email", "alice@example.com", 1);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let mut doc_b = create_document(1);
+        add_text_field(&mut doc_b, "applicant", "Bob", 1);
+        let buffer_b = save_to_buffer(&mut doc_b);
+        let buffer_c = save_to_buffer(&mut create_document(1));
+        let mut doc_d = create_document(1);
+        add_text_field(&mut doc_d, "applicant", "Carol", 1);
+        let buffer_d = save_to_buffer(&mut doc_d);
+        let inputs = vec![
+            MergeInput::new("a.pdf", &buffer_a),
+            MergeInput::new("b.pdf", &buffer_b),
+            MergeInput::new("c.pdf", &buffer_c),
+            MergeInput::new("d.pdf", &buffer_d),
+        ];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        let acro_form = result
+            .catalog()
+            .and_then(|catalog| catalog.get(b"AcroForm"))
+            .and_then(Object::as_dict)
+            .unwrap();
+        let fields: Vec<(String, String)> = acro_form
+            .get(b"Fields")
+            .and_then(Object::as_array)
+            .unwrap()
+            .iter()
+            .map(|field| {
+                let field = result
+                    .get_dictionary(field.as_reference().unwrap())
+                    .unwrap();
+                (
+                    decode_text_string(field.get(b"T").unwrap()).unwrap(),
+                    decode_text_string(field.get(b"V").unwrap()).unwrap(),
+                )
+            })
+            .collect();
+        let expected = vec![
+            ("applicant".to_string(), "Alice".to_string()),
+            ("email".to_string(), "alice@example.com".to_string()),
+            ("applicant_2".to_string(), "Bob".to_string()),
+            ("applicant_3".to_string(), "Carol".to_string()),
+        ];
+        assert_eq!(expected, fields);
+    }
+    #[test]
+    fn merge_pdf_from_mem_acro_form_ok_2() {
+        // Only the fields on the selected pages are merged
+        let mut doc_a = create_document(3);
+        add_text_field(&mut doc_a, "first", "1", 1);
+        add_text_field(&mut doc_a, "second", "2", 2);
+        add_text_field(&mut doc_a, "third", "3", 3);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let inputs = vec![MergeInput {
+            page_range: Some("2".to_string()),
+            ..MergeInput::new("a.pdf", &buffer_a)
+        }];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default());
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        let page_id = result.page_iter().next().unwrap();
+        let fields = result
+            .catalog()
+            .and_then(|catalog| catalog.get(b"AcroForm"))
+            .and_then(Object::as_dict)
+            .and_then(|acro_form| acro_form.get(b"Fields"))
+            .and_then(Object::as_array)
+            .unwrap();
+        assert_eq!(1, fields.len());
+        let field = result
+            .get_dictionary(fields[0].as_reference().unwrap())
+            .unwrap();
+        assert_eq!(
+            "second",
+            decode_text_string(field.get(b"T").unwrap()).unwrap()
+        );
+        assert_eq!(page_id, field.get(b"P").unwrap().as_reference().unwrap());
+    }
+    #[test]
+    fn merge_pdf_from_mem_acro_form_ok_3() {
+        // A renamed field does not take the name of another field of its document
+        let mut doc_a = create_document(1);
+        add_text_field(&mut doc_a, "a", "1", 1);
+        let buffer_a = save_to_buffer(&mut doc_a);
+        let mut doc_b = create_document(1);
+        add_text_field(&mut doc_b, "a", "2", 1);
+        add_text_field(&mut doc_b, "a_2", "3", 1);
+        let buffer_b = save_to_buffer(&mut doc_b);
+        let inputs = vec![
+            MergeInput::new("a.pdf", &buffer_a),
+            MergeInput::new("b.pdf", &buffer_b),
+        ];
+        let result = MergeDocument::merge_pdf_from_mem(inputs, &MergeOptions::default()).unwrap();
+        let fields: Vec<(String, String)> = result
+            .catalog()
+            .and_then(|catalog| catalog.get(b"AcroForm"))
+            .and_then(Object::as_dict)
+            .and_then(|acro_form| acro_form.get(b"Fields"))
+            .and_then(Object::as_array)
+            .unwrap()
+            .iter()
+            .map(|field| {
+                let field = result
+                    .get_dictionary(field.as_reference().unwrap())
+                    .unwrap();
+                (
+                    decode_text_string(field.get(b"T").unwrap()).unwrap(),
+                    decode_text_string(field.get(b"V").unwrap()).unwrap(),
+                )
+            })
+            .collect();
+        let expected = vec![
+            ("a".to_string(), "1".to_string()),
+            ("a_3".to_string(), "2".to_string()),
+            ("a_2".to_string(), "3".to_string()),
+        ];
+        assert_eq!(expected, fields);
+    }
 }
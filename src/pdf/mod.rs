@@ -1,7 +1,30 @@
+mod compress;
+mod convert;
+mod decrypt;
+mod encrypt;
 mod error;
 mod merge;
+mod optimize;
+mod organize;
+mod outline;
+mod page_range;
+mod rotate;
+mod security_handler;
 mod split;
+#[cfg(test)]
+mod test_utils;
+mod thumbnail;
 
+pub use compress::{CompressDocument, CompressOptions};
+pub use convert::{ConvertDocument, ImagePageOptions, PageOrientation, PageSize};
+pub use decrypt::DecryptDocument;
+pub use encrypt::{EncryptDocument, Permissions};
 pub use error::{Error, Result};
-pub use merge::MergeDocument;
+pub use merge::{MergeDocument, MergeInput, MergeMode, MergeOptions};
+pub use optimize::OptimizeDocument;
+pub use organize::OrganizeDocument;
+pub use outline::OutlineItem;
+pub use page_range::{PageRangeError, PageRanges};
+pub use rotate::RotateDocument;
 pub use split::SplitDocumnet;
+pub use thumbnail::{RenderImageFormat, RenderImageOptions, ThumbnailDocument, ThumbnailRenderer};
@@ -0,0 +1,151 @@
+use lopdf::{Document, Object, ObjectId};
+use md5::{Digest, Md5};
+use std::collections::HashMap;
+
+pub struct OptimizeDocument;
+
+impl OptimizeDocument {
+    // Merge byte-identical streams (fonts, images, ICC profiles) and remove the objects
+    // nothing refers to. Returns the stream bytes saved, the saved file shrinks by about as much.
+    pub fn optimize(doc: &mut Document) -> usize {
+        let stream_bytes = Self::_stream_bytes(doc);
+        // Streams referring to merged streams may become identical in turn
+        while Self::_merge_identical_streams(doc) {}
+        doc.prune_objects();
+
+        stream_bytes.saturating_sub(Self::_stream_bytes(doc))
+    }
+
+    // Point every reference to a duplicate stream at the first copy, returns false without duplicates
+    fn _merge_identical_streams(doc: &mut Document) -> bool {
+        let mut streams_by_digest: HashMap<Vec<u8>, Vec<ObjectId>> = HashMap::new();
+        for (object_id, object) in &doc.objects {
+            let Object::Stream(stream) = object else {
+                continue;
+            };
+            // The writer drops these, they are rebuilt on save
+            if stream.dict.type_is(b"ObjStm") || stream.dict.type_is(b"XRef") {
+                continue;
+            }
+            let digest = Md5::digest(&stream.content).to_vec();
+            streams_by_digest
+                .entry(digest)
+                .or_default()
+                .push(*object_id);
+        }
+
+        let mut replacements: HashMap<ObjectId, ObjectId> = HashMap::new();
+        for object_ids in streams_by_digest.values() {
+            for (idx, object_id) in object_ids.iter().enumerate() {
+                // Same digest, compare the dictionaries and the bytes as well
+                if let Some(first_id) = object_ids[..idx]
+                    .iter()
+                    .find(|first_id| doc.objects.get(first_id) == doc.objects.get(object_id))
+                {
+                    replacements.insert(*object_id, *first_id);
+                }
+            }
+        }
+        if replacements.is_empty() {
+            return false;
+        }
+
+        for object in doc.objects.values_mut() {
+            Self::_replace_references(object, &replacements);
+        }
+        for (_, object) in doc.trailer.iter_mut() {
+            Self::_replace_references(object, &replacements);
+        }
+        for object_id in replacements.keys() {
+            doc.objects.remove(object_id);
+        }
+
+        true
+    }
+
+    fn _replace_references(object: &mut Object, replacements: &HashMap<ObjectId, ObjectId>) {
+        match object {
+            Object::Reference(object_id) => {
+                if let Some(new_id) = replacements.get(object_id) {
+                    *object_id = *new_id;
+                }
+            }
+            Object::Array(array) => array
+                .iter_mut()
+                .for_each(|object| Self::_replace_references(object, replacements)),
+            Object::Dictionary(dict) => dict
+                .iter_mut()
+                .for_each(|(_, object)| Self::_replace_references(object, replacements)),
+            Object::Stream(stream) => stream
+                .dict
+                .iter_mut()
+                .for_each(|(_, object)| Self::_replace_references(object, replacements)),
+            _ => {}
+        }
+    }
+
+    fn _stream_bytes(doc: &Document) -> usize {
+        doc.objects
+            .values()
+            .filter_map(|object| match object {
+                Object::Stream(stream) => Some(stream.content.len()),
+                _ => None,
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{create_document, page_texts, save_to_buffer};
+    use lopdf::{dictionary, Stream};
+
+    #[test]
+    fn optimize_ok_1() {
+        // Two copies of the same embedded font, the second one's descriptor is identical as well
+        let mut doc = create_document(2);
+        let font_file = vec![7u8; 1000];
+        let mut descriptor_ids: Vec<ObjectId> = Vec::new();
+        for _ in 0..2 {
+            let font_file_id = doc.add_object(Stream::new(dictionary! {}, font_file.clone()));
+            descriptor_ids.push(doc.add_object(Stream::new(
+                dictionary! { "FontFile2" => font_file_id },
+                vec![1, 2, 3],
+            )));
+        }
+        let pages = doc.get_pages();
+        for (page_number, descriptor_id) in [1, 2].iter().zip(&descriptor_ids) {
+            doc.get_dictionary_mut(pages[page_number])
+                .unwrap()
+                .set("Descriptor", *descriptor_id);
+        }
+        // Nothing refers to this one
+        doc.add_object(Stream::new(dictionary! {}, vec![9u8; 500]));
+        let object_count = doc.objects.len();
+
+        let result = OptimizeDocument::optimize(&mut doc);
+        assert_eq!(1000 + 3 + 500, result);
+        assert_eq!(object_count - 3, doc.objects.len());
+        let descriptor = |page_number: u32| {
+            doc.get_dictionary(pages[&page_number])
+                .unwrap()
+                .get(b"Descriptor")
+                .unwrap()
+                .as_reference()
+                .unwrap()
+        };
+        assert_eq!(descriptor(1), descriptor(2));
+        let buffer = save_to_buffer(&mut doc);
+        let doc = Document::load_mem(&buffer).unwrap();
+        assert_eq!(vec!["Page 1", "Page 2"], page_texts(&doc));
+    }
+    #[test]
+    fn optimize_ok_2() {
+        let mut doc = create_document(2);
+        let object_count = doc.objects.len();
+        let result = OptimizeDocument::optimize(&mut doc);
+        assert_eq!(0, result);
+        assert_eq!(object_count, doc.objects.len());
+    }
+}
@@ -0,0 +1,676 @@
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId};
+use std::collections::HashSet;
+
+use super::{DecryptDocument, Error, OutlineItem, Result, SplitDocumnet};
+
+// Page attributes a page inherits from its parent /Pages nodes
+const INHERITABLE_ATTRIBUTES: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+// Catalog entries copy_pages rebuilds, or drops as the structure tree describes every page
+const CATALOG_ENTRIES_NOT_COPIED: [&[u8]; 6] = [
+    b"Type",
+    b"Pages",
+    b"AcroForm",
+    b"Outlines",
+    b"StructTreeRoot",
+    b"MarkInfo",
+];
+// Page and field trees are shallow, stop following /Parent or /Kids after a while in case they loop.
+const MAX_PARENT_DEPTH: usize = 32;
+
+pub struct OrganizeDocument;
+
+impl OrganizeDocument {
+    // Remove the pages, e.g. "3, 7-9", and keep the rest of the document
+    pub fn remove_pages_from_mem(buffer: &[u8], page_numbers_str: &str) -> Result<Document> {
+        let mut doc = DecryptDocument::load_mem(buffer, "")?;
+        Self::remove_pages(&mut doc, page_numbers_str)?;
+
+        Ok(doc)
+    }
+
+    // Keep only the pages, e.g. "3, 7-9", in a single document
+    pub fn extract_pages_from_mem(buffer: &[u8], page_numbers_str: &str) -> Result<Document> {
+        let mut doc = DecryptDocument::load_mem(buffer, "")?;
+        Self::extract_pages(&mut doc, page_numbers_str)?;
+
+        Ok(doc)
+    }
+
+    pub fn page_count_from_mem(buffer: &[u8]) -> Result<u32> {
+        let doc = DecryptDocument::load_mem(buffer, "")?;
+
+        Ok(doc.get_pages().len() as u32)
+    }
+
+    // Rebuild the document with the pages in the given order, e.g. [3, 1, 1, 2].
+    // A page listed more than once is duplicated, a page not listed is deleted.
+    pub fn arrange_pages_from_mem(buffer: &[u8], page_order: &[u32]) -> Result<Document> {
+        let mut doc = DecryptDocument::load_mem(buffer, "")?;
+        Self::arrange_pages(&mut doc, page_order)?;
+
+        Ok(doc)
+    }
+
+    pub fn arrange_pages(doc: &mut Document, page_order: &[u32]) -> Result<()> {
+        if page_order.is_empty() {
+            return Err(Error::CannotRemoveAllPages);
+        }
+        let pages = doc.get_pages();
+        if page_order.contains(&0) {
+            return Err(Error::InValidPageNumbers);
+        }
+        if page_order
+            .iter()
+            .any(|page_number| !pages.contains_key(page_number))
+        {
+            return Err(Error::PageNumberOverFlow);
+        }
+        let pages_id = doc
+            .catalog()
+            .and_then(|catalog| catalog.get(b"Pages"))
+            .and_then(Object::as_reference)
+            .map_err(|_| Error::CatalogObjectNotFound)?;
+
+        // Read before the page tree changes, the bookmarks of the dropped pages are removed
+        let outlines = OutlineItem::read_from_document(doc);
+        let mut kids: Vec<Object> = Vec::new();
+        let mut is_used: Vec<bool> = vec![false; pages.len()];
+        for page_number in page_order {
+            let page_id = pages[page_number];
+            let is_duplicate = std::mem::replace(&mut is_used[*page_number as usize - 1], true);
+            // Intermediate /Pages nodes are dropped
+            let page = Self::_copy_page(doc, page_id, pages_id, is_duplicate)?;
+            if is_duplicate {
+                kids.push(doc.add_object(page).into());
+            } else {
+                doc.objects.insert(page_id, Object::Dictionary(page));
+                kids.push(page_id.into());
+            }
+        }
+
+        let pages_dict = doc
+            .get_dictionary_mut(pages_id)
+            .map_err(|_| Error::PageObjectNotFound)?;
+        pages_dict.set("Count", kids.len() as i64);
+        pages_dict.set("Kids", kids);
+        let dropped_ids: HashSet<ObjectId> = pages
+            .iter()
+            .filter(|(page_number, _)| !is_used[**page_number as usize - 1])
+            .map(|(_, page_id)| *page_id)
+            .collect();
+        if !dropped_ids.is_empty() {
+            Self::_retain_page_fields(doc);
+            // Links, named destinations and widgets would keep the dropped pages in the document
+            for object in doc.objects.values_mut() {
+                Self::_collect_references(object, &dropped_ids, &mut Vec::new());
+            }
+            let has_outlines = doc
+                .catalog_mut()
+                .is_ok_and(|catalog| catalog.remove(b"Outlines").is_some());
+            if has_outlines {
+                let page_ids: HashSet<ObjectId> = doc.page_iter().collect();
+                let outlines = OutlineItem::retain_pages(&outlines, &page_ids);
+                OutlineItem::add_bookmarks(doc, &outlines, None);
+                OutlineItem::build_outline(doc);
+            }
+        }
+        // Drop the deleted pages and the intermediate /Pages nodes
+        doc.prune_objects();
+
+        Ok(())
+    }
+
+    // A new document with the pages in the given order, e.g. [3, 1], and only the objects
+    // they use. The source document is not cloned, this is cheap for a page of a large document.
+    pub fn copy_pages(doc: &Document, page_numbers: &[u32]) -> Result<Document> {
+        if page_numbers.is_empty() {
+            return Err(Error::CannotRemoveAllPages);
+        }
+        let pages = doc.get_pages();
+        if page_numbers.contains(&0) {
+            return Err(Error::InValidPageNumbers);
+        }
+        if page_numbers
+            .iter()
+            .any(|page_number| !pages.contains_key(page_number))
+        {
+            return Err(Error::PageNumberOverFlow);
+        }
+        // References to pages that are not copied and to the page tree become null,
+        // e.g. a link to another page would otherwise pull in the whole document
+        let mut excluded_ids: HashSet<ObjectId> = doc
+            .objects
+            .iter()
+            .filter(|(_, object)| {
+                object
+                    .as_dict()
+                    .is_ok_and(|dict| dict.type_is(b"Pages") || dict.type_is(b"Catalog"))
+            })
+            .map(|(object_id, _)| *object_id)
+            .collect();
+        excluded_ids.extend(
+            pages
+                .iter()
+                .filter(|(page_number, _)| !page_numbers.contains(page_number))
+                .map(|(_, page_id)| *page_id),
+        );
+
+        let mut new_doc = Document::with_version(doc.version.as_str());
+        // Objects keep their id, new objects are numbered after the source objects
+        new_doc.max_id = doc.max_id;
+        let pages_id = new_doc.new_object_id();
+        new_doc
+            .objects
+            .insert(pages_id, Object::Dictionary(Dictionary::new()));
+        let mut referenced_ids: Vec<ObjectId> = Vec::new();
+        let mut kids: Vec<Object> = Vec::new();
+        for page_number in page_numbers {
+            let page_id = pages[page_number];
+            let is_duplicate = new_doc.objects.contains_key(&page_id);
+            // Intermediate /Pages nodes are not copied
+            let page = Self::_copy_page(doc, page_id, pages_id, is_duplicate)?;
+            let mut page = Object::Dictionary(page);
+            Self::_collect_references(&mut page, &excluded_ids, &mut referenced_ids);
+            if is_duplicate {
+                kids.push(new_doc.add_object(page).into());
+            } else {
+                new_doc.objects.insert(page_id, page);
+                kids.push(page_id.into());
+            }
+        }
+        Self::_copy_objects(doc, &mut new_doc, &excluded_ids, referenced_ids);
+        new_doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => kids.len() as i64,
+                "Kids" => kids,
+            }),
+        );
+
+        let mut catalog = dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        };
+        // Keep the form fields of the copied widgets
+        if let Ok(acro_form) = doc
+            .catalog()
+            .and_then(|catalog| catalog.get_deref(b"AcroForm", doc))
+            .and_then(Object::as_dict)
+        {
+            let mut acro_form = acro_form.clone();
+            if let Ok(Object::Array(fields)) = acro_form.get_mut(b"Fields") {
+                fields.retain(|field| {
+                    field
+                        .as_reference()
+                        .is_ok_and(|field_id| new_doc.objects.contains_key(&field_id))
+                });
+            }
+            let mut acro_form = Object::Dictionary(acro_form);
+            let mut referenced_ids: Vec<ObjectId> = Vec::new();
+            Self::_collect_references(&mut acro_form, &excluded_ids, &mut referenced_ids);
+            Self::_copy_objects(doc, &mut new_doc, &excluded_ids, referenced_ids);
+            catalog.set("AcroForm", acro_form);
+        }
+        // Keep the other entries, e.g. /Names, /PageLabels, /Lang or /OCProperties
+        if let Ok(source_catalog) = doc.catalog() {
+            for (key, value) in source_catalog.iter() {
+                if CATALOG_ENTRIES_NOT_COPIED.contains(&key.as_slice()) {
+                    continue;
+                }
+                let mut value = value.clone();
+                let mut referenced_ids: Vec<ObjectId> = Vec::new();
+                Self::_collect_references(&mut value, &excluded_ids, &mut referenced_ids);
+                Self::_copy_objects(doc, &mut new_doc, &excluded_ids, referenced_ids);
+                catalog.set(key.clone(), value);
+            }
+        }
+        let catalog_id = new_doc.add_object(catalog);
+        new_doc.trailer.set("Root", catalog_id);
+        if let Ok(info_id) = doc.trailer.get(b"Info").and_then(Object::as_reference) {
+            Self::_copy_objects(doc, &mut new_doc, &excluded_ids, vec![info_id]);
+            new_doc.trailer.set("Info", info_id);
+        }
+        // Keep the bookmarks pointing to the copied pages, the copies of a duplicated page
+        // are not bookmarked
+        let page_ids: HashSet<ObjectId> = page_numbers
+            .iter()
+            .map(|page_number| pages[page_number])
+            .collect();
+        let outlines = OutlineItem::retain_pages(&OutlineItem::read_from_document(doc), &page_ids);
+        OutlineItem::add_bookmarks(&mut new_doc, &outlines, None);
+        new_doc.renumber_objects();
+        OutlineItem::build_outline(&mut new_doc);
+
+        Ok(new_doc)
+    }
+
+    pub fn remove_pages(doc: &mut Document, page_numbers_str: &str) -> Result<()> {
+        let max_pages = doc.get_pages().len() as u32;
+        let delete_page_numbers =
+            SplitDocumnet::page_numbers_from_str(page_numbers_str, max_pages)?;
+
+        Self::_delete_pages(doc, &delete_page_numbers, max_pages)
+    }
+
+    pub fn extract_pages(doc: &mut Document, page_numbers_str: &str) -> Result<()> {
+        let max_pages = doc.get_pages().len() as u32;
+        let page_numbers = SplitDocumnet::page_numbers_from_str(page_numbers_str, max_pages)?;
+        let delete_page_numbers: Vec<u32> = (1..max_pages + 1)
+            .filter(|page_number| !page_numbers.contains(page_number))
+            .collect();
+
+        Self::_delete_pages(doc, &delete_page_numbers, max_pages)
+    }
+
+    // The attribute of the page or, when the page does not set it, of its nearest parent,
+    // e.g. /Rotate or /MediaBox
+    pub fn inherited_page_attribute<'a>(
+        doc: &'a Document,
+        page_id: ObjectId,
+        key: &[u8],
+    ) -> Option<&'a Object> {
+        let mut node_id = page_id;
+        for _ in 0..MAX_PARENT_DEPTH {
+            let node = doc.get_dictionary(node_id).ok()?;
+            if let Ok(value) = node.get(key) {
+                return Some(value);
+            }
+            node_id = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+        }
+
+        None
+    }
+
+    // The page under the /Pages node `pages_id`, with what it inherits from its parents moved onto it.
+    // Annotations belong to a single page, a duplicate has none as widgets would share their form field.
+    fn _copy_page(
+        doc: &Document,
+        page_id: ObjectId,
+        pages_id: ObjectId,
+        is_duplicate: bool,
+    ) -> Result<Dictionary> {
+        let mut page = Self::_inherited_page(doc, page_id)?;
+        page.set("Parent", pages_id);
+        if is_duplicate {
+            page.remove(b"Annots");
+        }
+
+        Ok(page)
+    }
+
+    fn _inherited_page(doc: &Document, page_id: ObjectId) -> Result<Dictionary> {
+        let mut page = doc
+            .get_dictionary(page_id)
+            .map_err(|_| Error::PageObjectNotFound)?
+            .clone();
+        for key in INHERITABLE_ATTRIBUTES {
+            if !page.has(key) {
+                if let Some(value) = Self::inherited_page_attribute(doc, page_id, key) {
+                    page.set(key, value.clone());
+                }
+            }
+        }
+
+        Ok(page)
+    }
+
+    // Keep the form fields with a widget on the remaining pages,
+    // the widgets of the deleted pages would point at a page that is gone
+    fn _retain_page_fields(doc: &mut Document) {
+        let widget_ids: HashSet<ObjectId> = doc
+            .page_iter()
+            .filter_map(|page_id| doc.get_dictionary(page_id).ok())
+            .filter_map(|page| {
+                page.get_deref(b"Annots", doc)
+                    .and_then(Object::as_array)
+                    .ok()
+            })
+            .flatten()
+            .filter_map(|annot| annot.as_reference().ok())
+            .collect();
+        let Ok(acro_form) = doc
+            .catalog()
+            .and_then(|catalog| catalog.get(b"AcroForm"))
+            .cloned()
+        else {
+            return;
+        };
+        let field_ids: Vec<ObjectId> = doc
+            .dereference(&acro_form)
+            .and_then(|(_, acro_form)| acro_form.as_dict())
+            .and_then(|acro_form| acro_form.get_deref(b"Fields", doc))
+            .and_then(Object::as_array)
+            .map(|fields| {
+                fields
+                    .iter()
+                    .filter_map(|field| field.as_reference().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let fields: Vec<Object> = field_ids
+            .into_iter()
+            .filter(|field_id| Self::_retain_field_widgets(doc, *field_id, &widget_ids, 0))
+            .map(Object::Reference)
+            .collect();
+
+        let acro_form = match acro_form {
+            Object::Reference(acro_form_id) => doc.get_dictionary_mut(acro_form_id).ok(),
+            _ => doc
+                .catalog_mut()
+                .and_then(|catalog| catalog.get_mut(b"AcroForm"))
+                .and_then(Object::as_dict_mut)
+                .ok(),
+        };
+        if let Some(acro_form) = acro_form {
+            acro_form.set("Fields", fields);
+        }
+    }
+
+    // Drop the widgets that are not in `widget_ids` from the field tree,
+    // returns false when the field has no widget left
+    fn _retain_field_widgets(
+        doc: &mut Document,
+        field_id: ObjectId,
+        widget_ids: &HashSet<ObjectId>,
+        depth: usize,
+    ) -> bool {
+        if widget_ids.contains(&field_id) {
+            return true;
+        }
+        if depth >= MAX_PARENT_DEPTH {
+            return false;
+        }
+        let Ok(kid_ids) = doc
+            .get_dictionary(field_id)
+            .and_then(|field| field.get(b"Kids"))
+            .and_then(Object::as_array)
+            .map(|kids| {
+                kids.iter()
+                    .filter_map(|kid| kid.as_reference().ok())
+                    .collect::<Vec<ObjectId>>()
+            })
+        else {
+            return false;
+        };
+        let kids: Vec<Object> = kid_ids
+            .into_iter()
+            .filter(|kid_id| Self::_retain_field_widgets(doc, *kid_id, widget_ids, depth + 1))
+            .map(Object::Reference)
+            .collect();
+        let is_retained = !kids.is_empty();
+        if let Ok(field) = doc.get_dictionary_mut(field_id) {
+            field.set("Kids", kids);
+        }
+
+        is_retained
+    }
+
+    // Copy the objects and everything they reference from `doc` into `new_doc`, keeping their id
+    fn _copy_objects(
+        doc: &Document,
+        new_doc: &mut Document,
+        excluded_ids: &HashSet<ObjectId>,
+        mut object_ids: Vec<ObjectId>,
+    ) {
+        while let Some(object_id) = object_ids.pop() {
+            if new_doc.objects.contains_key(&object_id) {
+                continue;
+            }
+            // A dangling reference stays dangling, readers treat it as null
+            let Ok(object) = doc.get_object(object_id) else {
+                continue;
+            };
+            let mut object = object.clone();
+            Self::_collect_references(&mut object, excluded_ids, &mut object_ids);
+            new_doc.objects.insert(object_id, object);
+        }
+    }
+
+    // Push the references of the object, and replace the excluded ones with null
+    fn _collect_references(
+        object: &mut Object,
+        excluded_ids: &HashSet<ObjectId>,
+        object_ids: &mut Vec<ObjectId>,
+    ) {
+        match object {
+            Object::Reference(object_id) => {
+                if excluded_ids.contains(object_id) {
+                    *object = Object::Null;
+                } else {
+                    object_ids.push(*object_id);
+                }
+            }
+            Object::Array(array) => array
+                .iter_mut()
+                .for_each(|object| Self::_collect_references(object, excluded_ids, object_ids)),
+            Object::Dictionary(dict) => dict.iter_mut().for_each(|(_, object)| {
+                Self::_collect_references(object, excluded_ids, object_ids)
+            }),
+            Object::Stream(stream) => stream.dict.iter_mut().for_each(|(_, object)| {
+                Self::_collect_references(object, excluded_ids, object_ids)
+            }),
+            _ => {}
+        }
+    }
+
+    fn _delete_pages(
+        doc: &mut Document,
+        delete_page_numbers: &[u32],
+        max_pages: u32,
+    ) -> Result<()> {
+        if delete_page_numbers.len() as u32 >= max_pages {
+            return Err(Error::CannotRemoveAllPages);
+        }
+        if !delete_page_numbers.is_empty() {
+            doc.delete_pages(delete_page_numbers);
+            Self::_retain_page_fields(doc);
+            // Drop the content, fonts and images only used by the deleted pages
+            doc.prune_objects();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{
+        add_outline, add_text_field, create_document, page_texts, save_to_buffer,
+    };
+
+    #[test]
+    fn remove_pages_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(9));
+        let result = OrganizeDocument::remove_pages_from_mem(&buffer, "3, 7-9");
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 1", "Page 2", "Page 4", "Page 5", "Page 6"];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn remove_pages_from_mem_ok_2() {
+        let buffer = save_to_buffer(&mut create_document(3));
+        let result = OrganizeDocument::remove_pages_from_mem(&buffer, "1-3");
+        assert!(matches!(result, Err(Error::CannotRemoveAllPages)));
+        let result = OrganizeDocument::remove_pages_from_mem(&buffer, "4");
+        assert!(
+            matches!(result, Err(Error::InValidPageRange(e)) if e.token == "4" && e.offset == 0)
+        );
+    }
+    #[test]
+    fn arrange_pages_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(4));
+        let result = OrganizeDocument::arrange_pages_from_mem(&buffer, &[3, 1, 1, 2]);
+        assert!(result.is_ok());
+        let mut doc = result.unwrap();
+        let result = page_texts(&doc);
+        let expected = vec!["Page 3", "Page 1", "Page 1", "Page 2"];
+        assert_eq!(expected, result);
+        // The saved document reads back in the new order
+        let buffer = save_to_buffer(&mut doc);
+        let doc = Document::load_mem(&buffer).unwrap();
+        assert_eq!(expected, page_texts(&doc));
+        assert_eq!(4, doc.page_iter().count());
+    }
+    #[test]
+    fn arrange_pages_from_mem_ok_2() {
+        // Attributes inherited from an intermediate /Pages node are kept
+        let mut doc = create_document(2);
+        let pages = doc.get_pages();
+        let root_pages_id = doc
+            .catalog()
+            .unwrap()
+            .get(b"Pages")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let node_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Parent" => root_pages_id,
+            "Kids" => vec![pages[&2].into()],
+            "Count" => 1,
+            "Rotate" => 90,
+        });
+        doc.get_dictionary_mut(pages[&2])
+            .unwrap()
+            .set("Parent", node_id);
+        let root_pages = doc.get_dictionary_mut(root_pages_id).unwrap();
+        root_pages.set("Kids", vec![pages[&1].into(), node_id.into()]);
+        let buffer = save_to_buffer(&mut doc);
+
+        let doc = OrganizeDocument::arrange_pages_from_mem(&buffer, &[2, 1]).unwrap();
+        assert_eq!(vec!["Page 2", "Page 1"], page_texts(&doc));
+        let page = doc.get_dictionary(doc.get_pages()[&1]).unwrap();
+        assert_eq!(90, page.get(b"Rotate").unwrap().as_i64().unwrap());
+        assert_eq!(
+            root_pages_id,
+            page.get(b"Parent").unwrap().as_reference().unwrap()
+        );
+    }
+    #[test]
+    fn arrange_pages_from_mem_ok_3() {
+        let buffer = save_to_buffer(&mut create_document(2));
+        let result = OrganizeDocument::arrange_pages_from_mem(&buffer, &[]);
+        assert!(matches!(result, Err(Error::CannotRemoveAllPages)));
+        let result = OrganizeDocument::arrange_pages_from_mem(&buffer, &[1, 3]);
+        assert!(matches!(result, Err(Error::PageNumberOverFlow)));
+    }
+    #[test]
+    fn arrange_pages_from_mem_ok_4() {
+        // A dropped page with a form field and a bookmark leaves nothing behind
+        let mut doc = create_document(3);
+        add_text_field(&mut doc, "first", "1", 1);
+        add_text_field(&mut doc, "second", "2", 2);
+        add_outline(&mut doc, &[("Two", 2), ("Three", 3)]);
+        let dropped_id = doc.get_pages()[&2];
+        let buffer = save_to_buffer(&mut doc);
+
+        let doc = OrganizeDocument::arrange_pages_from_mem(&buffer, &[3, 1]).unwrap();
+        assert_eq!(vec!["Page 3", "Page 1"], page_texts(&doc));
+        assert!(!doc.objects.contains_key(&dropped_id));
+        let fields = doc
+            .catalog()
+            .and_then(|catalog| catalog.get_deref(b"AcroForm", &doc))
+            .and_then(Object::as_dict)
+            .and_then(|acro_form| acro_form.get(b"Fields"))
+            .and_then(Object::as_array)
+            .unwrap();
+        assert_eq!(1, fields.len());
+        let outlines: Vec<(String, Option<ObjectId>)> = OutlineItem::read_from_document(&doc)
+            .into_iter()
+            .map(|item| (item.title, item.page_id))
+            .collect();
+        assert_eq!(
+            vec![("Three".to_string(), Some(doc.get_pages()[&1]))],
+            outlines
+        );
+    }
+    #[test]
+    fn copy_pages_ok_1() {
+        let mut doc = create_document(3);
+        let pages = doc.get_pages();
+        // A link on page 2 to page 3, which is not copied
+        let link_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Dest" => vec![pages[&3].into(), "Fit".into()],
+        });
+        doc.get_dictionary_mut(pages[&2])
+            .unwrap()
+            .set("Annots", vec![link_id.into()]);
+        add_text_field(&mut doc, "first", "1", 1);
+        add_text_field(&mut doc, "second", "2", 2);
+
+        let mut result = OrganizeDocument::copy_pages(&doc, &[2]).unwrap();
+        assert_eq!(vec!["Page 2"], page_texts(&result));
+        // Page, content, resources, font, link, widget, pages and catalog
+        assert_eq!(8, result.objects.len());
+        let fields = result
+            .catalog()
+            .unwrap()
+            .get_deref(b"AcroForm", &result)
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Fields")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(1, fields.len());
+        let buffer = save_to_buffer(&mut result);
+        let result = Document::load_mem(&buffer).unwrap();
+        assert_eq!(vec!["Page 2"], page_texts(&result));
+    }
+    #[test]
+    fn copy_pages_ok_2() {
+        let doc = create_document(3);
+        let result = OrganizeDocument::copy_pages(&doc, &[3, 1, 3]).unwrap();
+        assert_eq!(vec!["Page 3", "Page 1", "Page 3"], page_texts(&result));
+        let result = OrganizeDocument::copy_pages(&doc, &[]);
+        assert!(matches!(result, Err(Error::CannotRemoveAllPages)));
+        let result = OrganizeDocument::copy_pages(&doc, &[4]);
+        assert!(matches!(result, Err(Error::PageNumberOverFlow)));
+    }
+    #[test]
+    fn copy_pages_ok_3() {
+        let mut doc = create_document(4);
+        add_outline(&mut doc, &[("One", 1), ("Three", 3), ("Four", 4)]);
+        doc.catalog_mut()
+            .unwrap()
+            .set("Lang", Object::string_literal("en"));
+
+        let mut result = OrganizeDocument::copy_pages(&doc, &[3, 4, 3]).unwrap();
+        let result = Document::load_mem(&save_to_buffer(&mut result)).unwrap();
+        let pages = result.get_pages();
+        let outlines: Vec<(String, Option<ObjectId>)> = OutlineItem::read_from_document(&result)
+            .into_iter()
+            .map(|item| (item.title, item.page_id))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Three".to_string(), Some(pages[&1])),
+                ("Four".to_string(), Some(pages[&2])),
+            ],
+            outlines
+        );
+        assert_eq!(
+            Some(b"en".as_slice()),
+            result
+                .catalog()
+                .and_then(|catalog| catalog.get(b"Lang"))
+                .and_then(Object::as_str)
+                .ok()
+        );
+    }
+    #[test]
+    fn extract_pages_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(9));
+        let result = OrganizeDocument::extract_pages_from_mem(&buffer, "7-9, 3");
+        assert!(result.is_ok());
+        let result = page_texts(&result.unwrap());
+        let expected = vec!["Page 3", "Page 7", "Page 8", "Page 9"];
+        assert_eq!(expected, result);
+    }
+}
@@ -0,0 +1,249 @@
+use lopdf::{decode_text_string, text_string, Bookmark, Dictionary, Document, Object, ObjectId};
+use std::collections::HashSet;
+
+// Named destinations and name trees may loop, stop following them after a while.
+const MAX_LOOKUP_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineItem {
+    pub title: String,
+    // None when the item only groups its children without pointing to a page itself
+    pub page_id: Option<ObjectId>,
+    // 0 for normal, 1 for italic, 2 for bold, 3 for italic bold
+    pub format: u32,
+    // R,G,B
+    pub color: [f32; 3],
+    pub children: Vec<OutlineItem>,
+}
+
+impl OutlineItem {
+    pub fn new(title: &str, page_id: Option<ObjectId>, children: Vec<OutlineItem>) -> Self {
+        Self {
+            title: title.to_string(),
+            page_id,
+            format: 0,
+            color: [0.0, 0.0, 0.0],
+            children,
+        }
+    }
+
+    // Read the outline (bookmark) tree of a document.
+    // Items pointing to a page outside of the document (remote or broken destinations)
+    // are dropped unless they still have children.
+    pub fn read_from_document(doc: &Document) -> Vec<OutlineItem> {
+        let Ok(catalog) = doc.catalog() else {
+            return Vec::new();
+        };
+        let Ok(outlines) = doc.get_dict_in_dict(catalog, b"Outlines") else {
+            return Vec::new();
+        };
+        let pages: HashSet<ObjectId> = doc.page_iter().collect();
+        let mut visited: HashSet<ObjectId> = HashSet::new();
+
+        Self::_read_siblings(doc, outlines.get(b"First").ok(), &pages, &mut visited)
+    }
+
+    // Keep the items pointing to one of the pages, e.g. the pages copied to a new document.
+    // Items left without a page are dropped unless they still have children.
+    pub fn retain_pages(items: &[OutlineItem], page_ids: &HashSet<ObjectId>) -> Vec<OutlineItem> {
+        items
+            .iter()
+            .filter_map(|item| {
+                let children = Self::retain_pages(&item.children, page_ids);
+                let page_id = item.page_id.filter(|page_id| page_ids.contains(page_id));
+                (page_id.is_some() || !children.is_empty()).then(|| OutlineItem {
+                    page_id,
+                    children,
+                    ..item.clone()
+                })
+            })
+            .collect()
+    }
+
+    // Add the items as bookmarks, they are written to the document by `build_outline`
+    pub fn add_bookmarks(doc: &mut Document, items: &[OutlineItem], parent: Option<u32>) {
+        for item in items {
+            // Bookmarks without a page get the page of their first child with `adjust_zero_pages`
+            let bookmark = Bookmark::new(
+                item.title.clone(),
+                item.color,
+                item.format,
+                item.page_id.unwrap_or((0, 0)),
+            );
+            let bookmark_id = doc.add_bookmark(bookmark, parent);
+            Self::add_bookmarks(doc, &item.children, Some(bookmark_id));
+        }
+    }
+
+    // Write the bookmarks as the outline of the catalog, once the objects are numbered
+    pub fn build_outline(doc: &mut Document) {
+        doc.adjust_zero_pages();
+        if let Some(outlines_id) = doc.build_outline() {
+            Self::_encode_titles(doc, outlines_id);
+            if let Ok(catalog) = doc.catalog_mut() {
+                catalog.set("Outlines", outlines_id);
+            }
+        }
+    }
+
+    fn _encode_titles(doc: &mut Document, outlines_id: ObjectId) {
+        // `build_outline` writes titles as raw UTF-8 bytes, re-encode them as PDF text strings
+        // so non-ASCII titles (e.g. Thai file names) display correctly.
+        let mut stack: Vec<ObjectId> = doc
+            .get_dictionary(outlines_id)
+            .and_then(|outlines| outlines.get(b"First"))
+            .and_then(Object::as_reference)
+            .into_iter()
+            .collect();
+        while let Some(item_id) = stack.pop() {
+            if let Ok(item) = doc.get_dictionary_mut(item_id) {
+                if let Ok(title) = item.get(b"Title").and_then(Object::as_str) {
+                    let title = String::from_utf8_lossy(title).to_string();
+                    item.set("Title", text_string(&title));
+                }
+                for key in [b"Next".as_slice(), b"First".as_slice()] {
+                    if let Ok(child_id) = item.get(key).and_then(Object::as_reference) {
+                        stack.push(child_id);
+                    }
+                }
+            }
+        }
+    }
+
+    fn _read_siblings(
+        doc: &Document,
+        first: Option<&Object>,
+        pages: &HashSet<ObjectId>,
+        visited: &mut HashSet<ObjectId>,
+    ) -> Vec<OutlineItem> {
+        let mut items: Vec<OutlineItem> = Vec::new();
+        let mut next = first.and_then(|object| object.as_reference().ok());
+        while let Some(node_id) = next {
+            // Malformed outlines may loop back to an item already read.
+            if !visited.insert(node_id) {
+                break;
+            }
+            let Ok(node) = doc.get_dictionary(node_id) else {
+                break;
+            };
+
+            let children = Self::_read_siblings(doc, node.get(b"First").ok(), pages, visited);
+            let page_id = Self::_resolve_page(doc, node).filter(|page_id| pages.contains(page_id));
+            if page_id.is_some() || !children.is_empty() {
+                items.push(OutlineItem {
+                    title: Self::_read_title(doc, node),
+                    page_id,
+                    format: node
+                        .get(b"F")
+                        .and_then(Object::as_i64)
+                        .map(|format| format as u32)
+                        .unwrap_or(0),
+                    color: Self::_read_color(node),
+                    children,
+                });
+            }
+
+            next = node.get(b"Next").and_then(Object::as_reference).ok();
+        }
+
+        items
+    }
+
+    fn _read_title(doc: &Document, node: &Dictionary) -> String {
+        node.get(b"Title")
+            .and_then(|title| doc.dereference(title))
+            .and_then(|(_, title)| decode_text_string(title))
+            .unwrap_or_default()
+    }
+
+    fn _read_color(node: &Dictionary) -> [f32; 3] {
+        let mut color = [0.0, 0.0, 0.0];
+        if let Ok(components) = node.get(b"C").and_then(Object::as_array) {
+            for (idx, component) in components.iter().take(3).enumerate() {
+                color[idx] = component.as_float().unwrap_or(0.0);
+            }
+        }
+
+        color
+    }
+
+    fn _resolve_page(doc: &Document, node: &Dictionary) -> Option<ObjectId> {
+        let destination = match node.get(b"Dest") {
+            Ok(destination) => destination,
+            Err(_) => {
+                let (_, action) = doc.dereference(node.get(b"A").ok()?).ok()?;
+                let action = action.as_dict().ok()?;
+                if action.get(b"S").and_then(Object::as_name).ok()? != b"GoTo" {
+                    return None;
+                }
+                action.get(b"D").ok()?
+            }
+        };
+
+        Self::_resolve_destination(doc, destination, 0)
+    }
+
+    fn _resolve_destination(
+        doc: &Document,
+        destination: &Object,
+        depth: usize,
+    ) -> Option<ObjectId> {
+        if depth > MAX_LOOKUP_DEPTH {
+            return None;
+        }
+        let (_, destination) = doc.dereference(destination).ok()?;
+        match destination {
+            // Explicit destination, e.g. [page /Fit]
+            Object::Array(array) => array.first()?.as_reference().ok(),
+            // Named destinations may be wrapped in a dictionary with the destination under /D
+            Object::Dictionary(dict) => {
+                Self::_resolve_destination(doc, dict.get(b"D").ok()?, depth + 1)
+            }
+            // PDF 1.1 named destination, stored in the /Dests dictionary of the catalog
+            Object::Name(name) => {
+                let dests = doc.get_dict_in_dict(doc.catalog().ok()?, b"Dests").ok()?;
+                Self::_resolve_destination(doc, dests.get(name).ok()?, depth + 1)
+            }
+            // PDF 1.2 named destination, stored in the /Dests name tree of the catalog /Names
+            Object::String(name, _) => {
+                let names = doc.get_dict_in_dict(doc.catalog().ok()?, b"Names").ok()?;
+                let dests = doc.get_dict_in_dict(names, b"Dests").ok()?;
+                let destination = Self::_find_in_name_tree(doc, dests, name, 0)?;
+                Self::_resolve_destination(doc, destination, depth + 1)
+            }
+            _ => None,
+        }
+    }
+
+    fn _find_in_name_tree<'a>(
+        doc: &'a Document,
+        node: &'a Dictionary,
+        key: &[u8],
+        depth: usize,
+    ) -> Option<&'a Object> {
+        if depth > MAX_LOOKUP_DEPTH {
+            return None;
+        }
+        if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+            for pair in names.chunks(2) {
+                if let [name, value] = pair {
+                    if name.as_str().ok() == Some(key) {
+                        return Some(value);
+                    }
+                }
+            }
+        }
+        if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+            for kid in kids {
+                let Ok(kid) = kid.as_reference().and_then(|id| doc.get_dictionary(id)) else {
+                    continue;
+                };
+                if let Some(value) = Self::_find_in_name_tree(doc, kid, key, depth + 1) {
+                    return Some(value);
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,565 @@
+use super::{Error, Result};
+
+// Page ranges, every comma separated item is one selection of pages:
+//
+//   ranges   := item ("," item)*
+//   item     := selector ("!" selector)*
+//   selector := "odd" | "even" | page | page? "-" page?
+//   page     := number | "last" ("-" number)?
+//
+// e.g. "1, 3-5, 8-, -2, last, last-1, odd, even, 10-1, 1-20 !5".
+// "last-1" is the page before the last one, write "5-last" for a range up to the last page.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PageRanges {
+    pub items: Vec<PageRangeItem>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PageRangeItem {
+    // Source text of the item, trimmed, and its character offset in the input
+    pub text: String,
+    pub offset: usize,
+    pub selector: PageSelector,
+    // Pages removed from the selection, e.g. "!5"
+    pub exclusions: Vec<PageSelector>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PageSelector {
+    Page(PageToken),
+    // Open ends are the first or the last page, a range from a higher to a lower page is reversed
+    Range(Option<PageToken>, Option<PageToken>),
+    Odd,
+    Even,
+}
+
+// A page reference with its source text and character offset, to point at it when it does not
+// fit the document
+#[derive(Debug, PartialEq, Clone)]
+pub struct PageToken {
+    pub page_ref: PageRef,
+    pub token: String,
+    pub offset: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PageRef {
+    Number(u32),
+    // Pages before the last page, "last" is Last(0)
+    Last(u32),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PageRangeError {
+    pub kind: PageRangeErrorKind,
+    // The offending token, empty at the end of the input, and its character offset in the input
+    pub token: String,
+    pub offset: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PageRangeErrorKind {
+    // Syntax errors
+    UnexpectedToken,
+    MissingPage,
+    NumberTooLarge,
+    // The ranges do not fit the document
+    PageZero,
+    PageOverFlow { page_number: u32, max_pages: u32 },
+    LastOffsetOverFlow { offset: u32, max_pages: u32 },
+    NoPagesSelected,
+}
+
+impl PageRanges {
+    pub fn parse(input: &str) -> Result<Self> {
+        if input.trim().is_empty() {
+            return Err(Error::SplitPagesStrIsEmpty);
+        }
+        let mut parser = Parser { input, position: 0 };
+        let mut items: Vec<PageRangeItem> = Vec::new();
+        loop {
+            items.push(parser.item()?);
+            parser.skip_whitespace();
+            match parser.peek() {
+                None => break,
+                Some(',') => parser.position += 1,
+                Some(_) => return Err(parser.error(PageRangeErrorKind::UnexpectedToken)),
+            }
+        }
+
+        Ok(Self { items })
+    }
+
+    // The pages of every item, in selection order
+    pub fn page_numbers(&self, max_pages: u32) -> Result<Vec<Vec<u32>>> {
+        self.items
+            .iter()
+            .map(|item| item.page_numbers(max_pages))
+            .collect()
+    }
+}
+
+impl PageRangeItem {
+    pub fn page_numbers(&self, max_pages: u32) -> Result<Vec<u32>> {
+        let mut page_numbers = self.selector.page_numbers(max_pages)?;
+        for exclusion in &self.exclusions {
+            let excluded_page_numbers = exclusion.page_numbers(max_pages)?;
+            page_numbers.retain(|page_number| !excluded_page_numbers.contains(page_number));
+        }
+        if page_numbers.is_empty() {
+            return Err(Error::InValidPageRange(PageRangeError {
+                kind: PageRangeErrorKind::NoPagesSelected,
+                token: self.text.clone(),
+                offset: self.offset,
+            }));
+        }
+
+        Ok(page_numbers)
+    }
+}
+
+impl PageSelector {
+    pub fn page_numbers(&self, max_pages: u32) -> Result<Vec<u32>> {
+        let page_numbers = match self {
+            Self::Page(page) => vec![page.page_number(max_pages)?],
+            Self::Range(first, last) => {
+                let first = first
+                    .as_ref()
+                    .map_or(Ok(1), |first| first.page_number(max_pages))?;
+                let last = last
+                    .as_ref()
+                    .map_or(Ok(max_pages), |last| last.page_number(max_pages))?;
+                if first <= last {
+                    (first..last + 1).collect()
+                } else {
+                    (last..first + 1).rev().collect()
+                }
+            }
+            Self::Odd => (1..max_pages + 1).step_by(2).collect(),
+            Self::Even => (2..max_pages + 1).step_by(2).collect(),
+        };
+
+        Ok(page_numbers)
+    }
+}
+
+impl PageToken {
+    pub fn page_number(&self, max_pages: u32) -> Result<u32> {
+        let kind = match self.page_ref {
+            PageRef::Number(0) => PageRangeErrorKind::PageZero,
+            PageRef::Number(page_number) if page_number > max_pages => {
+                PageRangeErrorKind::PageOverFlow {
+                    page_number,
+                    max_pages,
+                }
+            }
+            PageRef::Number(page_number) => return Ok(page_number),
+            PageRef::Last(offset) if offset >= max_pages => {
+                PageRangeErrorKind::LastOffsetOverFlow { offset, max_pages }
+            }
+            PageRef::Last(offset) => return Ok(max_pages - offset),
+        };
+
+        Err(Error::InValidPageRange(PageRangeError {
+            kind,
+            token: self.token.clone(),
+            offset: self.offset,
+        }))
+    }
+}
+
+impl PageRangeError {
+    // Byte range of the token in the input, at least one character to have something to point at
+    pub fn span(&self, input: &str) -> (usize, usize) {
+        let start = input
+            .char_indices()
+            .nth(self.offset)
+            .map_or(input.len(), |(idx, _)| idx);
+        let len = match self.token.len() {
+            0 => input[start..].chars().next().map_or(0, char::len_utf8),
+            len => len,
+        };
+        (start, (start + len).min(input.len()))
+    }
+}
+
+impl std::fmt::Display for PageRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let token = &self.token;
+        let column = self.offset + 1;
+        match self.kind {
+            PageRangeErrorKind::UnexpectedToken if token.is_empty() => {
+                write!(f, "expected a page at character {column}")
+            }
+            PageRangeErrorKind::UnexpectedToken => {
+                write!(f, "unexpected \"{token}\" at character {column}")
+            }
+            PageRangeErrorKind::MissingPage => write!(
+                f,
+                "\"-\" at character {column} needs a page before or after it"
+            ),
+            PageRangeErrorKind::NumberTooLarge => {
+                write!(f, "\"{token}\" at character {column} is too large")
+            }
+            PageRangeErrorKind::PageZero => {
+                write!(f, "page 0 at character {column}, pages start at 1")
+            }
+            PageRangeErrorKind::PageOverFlow {
+                page_number,
+                max_pages,
+            } => write!(
+                f,
+                "page {page_number} at character {column} is after the last page ({max_pages})"
+            ),
+            PageRangeErrorKind::LastOffsetOverFlow { offset, max_pages } => write!(
+                f,
+                "\"{token}\" at character {column} is {offset} pages before the last page, there are {max_pages} pages"
+            ),
+            PageRangeErrorKind::NoPagesSelected => {
+                write!(f, "\"{token}\" at character {column} selects no pages")
+            }
+        }
+    }
+}
+
+// Recursive descent over the input, `position` is a byte offset
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn item(&mut self) -> Result<PageRangeItem> {
+        self.skip_whitespace();
+        let offset = self.position;
+        let selector = self.selector()?;
+        let mut exclusions: Vec<PageSelector> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('!') {
+                break;
+            }
+            self.position += 1;
+            exclusions.push(self.selector()?);
+        }
+
+        Ok(PageRangeItem {
+            text: self.input[offset..self.position].trim().to_string(),
+            offset: self.column(offset),
+            selector,
+            exclusions,
+        })
+    }
+
+    fn selector(&mut self) -> Result<PageSelector> {
+        self.skip_whitespace();
+        let start = self.position;
+        match self.keyword().as_str() {
+            "odd" => return Ok(PageSelector::Odd),
+            "even" => return Ok(PageSelector::Even),
+            _ => self.position = start,
+        }
+        let first = self.page()?;
+        self.skip_whitespace();
+        if self.peek() != Some('-') {
+            return first
+                .map(PageSelector::Page)
+                .ok_or_else(|| self.error(PageRangeErrorKind::UnexpectedToken));
+        }
+        let dash = self.position;
+        self.position += 1;
+        self.skip_whitespace();
+        let last = self.page()?;
+        if first.is_none() && last.is_none() {
+            return Err(Error::InValidPageRange(PageRangeError {
+                kind: PageRangeErrorKind::MissingPage,
+                token: "-".to_string(),
+                offset: self.column(dash),
+            }));
+        }
+
+        Ok(PageSelector::Range(first, last))
+    }
+
+    // A page reference, or None when the next token is not one
+    fn page(&mut self) -> Result<Option<PageToken>> {
+        let start = self.position;
+        let page_ref = if let Some(page_number) = self.number()? {
+            PageRef::Number(page_number)
+        } else {
+            match self.keyword().as_str() {
+                "" => return Ok(None),
+                "last" => {
+                    // "last-1" is an offset, a range needs a page before the dash
+                    let after_last = self.position;
+                    let mut offset = 0;
+                    if self.peek() == Some('-') {
+                        self.position += 1;
+                        match self.number()? {
+                            Some(number) => offset = number,
+                            None => self.position = after_last,
+                        }
+                    }
+                    PageRef::Last(offset)
+                }
+                _ => {
+                    self.position = start;
+                    return Err(self.error(PageRangeErrorKind::UnexpectedToken));
+                }
+            }
+        };
+
+        Ok(Some(PageToken {
+            page_ref,
+            token: self.input[start..self.position].to_string(),
+            offset: self.column(start),
+        }))
+    }
+
+    fn number(&mut self) -> Result<Option<u32>> {
+        let start = self.position;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Ok(None);
+        }
+        let number = digits.parse().ok();
+        if number.is_none() {
+            self.position = start;
+            return Err(self.error(PageRangeErrorKind::NumberTooLarge));
+        }
+
+        Ok(number)
+    }
+
+    fn keyword(&mut self) -> String {
+        self.take_while(|c| c.is_ascii_alphabetic())
+            .to_ascii_lowercase()
+    }
+
+    // Error at the current position, the token is the word or the character found there
+    fn error(&self, kind: PageRangeErrorKind) -> Error {
+        let rest = &self.input[self.position..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        let len = match len {
+            0 => rest.chars().next().map_or(0, char::len_utf8),
+            len => len,
+        };
+
+        Error::InValidPageRange(PageRangeError {
+            kind,
+            token: rest[..len].to_string(),
+            offset: self.column(self.position),
+        })
+    }
+
+    // Character offset of the byte position, for error messages
+    fn column(&self, position: usize) -> usize {
+        self.input[..position].chars().count()
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &str {
+        let start = self.position;
+        let len = self.input[start..]
+            .find(|c: char| !predicate(c))
+            .unwrap_or(self.input.len() - start);
+        self.position += len;
+        &self.input[start..self.position]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page_ref(page: &Option<PageToken>) -> Option<PageRef> {
+        page.as_ref().map(|page| page.page_ref)
+    }
+
+    fn parse_error(input: &str) -> PageRangeError {
+        match PageRanges::parse(input) {
+            Err(Error::InValidPageRange(e)) => e,
+            result => panic!("{input}: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_ok_1() {
+        let result = PageRanges::parse("1, 3-5, 8-, -2, last, last-1, 5-last").unwrap();
+        let selectors: Vec<(Option<PageRef>, Option<PageRef>)> = result
+            .items
+            .iter()
+            .map(|item| match &item.selector {
+                PageSelector::Page(page) => (Some(page.page_ref), None),
+                PageSelector::Range(first, last) => (page_ref(first), page_ref(last)),
+                _ => (None, None),
+            })
+            .collect();
+        let expected = vec![
+            (Some(PageRef::Number(1)), None),
+            (Some(PageRef::Number(3)), Some(PageRef::Number(5))),
+            (Some(PageRef::Number(8)), None),
+            (None, Some(PageRef::Number(2))),
+            (Some(PageRef::Last(0)), None),
+            (Some(PageRef::Last(1)), None),
+            (Some(PageRef::Number(5)), Some(PageRef::Last(0))),
+        ];
+        assert_eq!(expected, selectors);
+        let texts: Vec<&str> = result.items.iter().map(|item| item.text.as_str()).collect();
+        assert_eq!(
+            vec!["1", "3-5", "8-", "-2", "last", "last-1", "5-last"],
+            texts
+        );
+    }
+    #[test]
+    fn parse_ok_2() {
+        let result = PageRanges::parse("ODD, even, 1-20 !5 !last-1").unwrap();
+        assert_eq!(PageSelector::Odd, result.items[0].selector);
+        assert_eq!(PageSelector::Even, result.items[1].selector);
+        let expected = vec![
+            PageSelector::Page(PageToken {
+                page_ref: PageRef::Number(5),
+                token: "5".to_string(),
+                offset: 17,
+            }),
+            PageSelector::Page(PageToken {
+                page_ref: PageRef::Last(1),
+                token: "last-1".to_string(),
+                offset: 20,
+            }),
+        ];
+        assert_eq!(expected, result.items[2].exclusions);
+        assert_eq!("1-20 !5 !last-1", result.items[2].text);
+        assert_eq!(11, result.items[2].offset);
+    }
+    #[test]
+    fn parse_ok_3() {
+        assert!(matches!(
+            PageRanges::parse(" "),
+            Err(Error::SplitPagesStrIsEmpty)
+        ));
+        let expected = vec![
+            ("1, 2-x", PageRangeErrorKind::UnexpectedToken, "x", 5),
+            ("1, 2-xy, 3", PageRangeErrorKind::UnexpectedToken, "xy", 5),
+            ("1,, 2", PageRangeErrorKind::UnexpectedToken, ",", 2),
+            ("1 2", PageRangeErrorKind::UnexpectedToken, "2", 2),
+            ("1,", PageRangeErrorKind::UnexpectedToken, "", 2),
+            ("3, -", PageRangeErrorKind::MissingPage, "-", 3),
+            (
+                "99999999999",
+                PageRangeErrorKind::NumberTooLarge,
+                "99999999999",
+                0,
+            ),
+        ];
+        for (input, kind, token, offset) in expected {
+            let result = parse_error(input);
+            assert_eq!(
+                (kind, token, offset),
+                (result.kind, result.token.as_str(), result.offset)
+            );
+        }
+    }
+    #[test]
+    fn page_numbers_ok_1() {
+        let result = PageRanges::parse("8-, -2, last-1, 10-7, odd, even, 1-10 !2-9 !last")
+            .unwrap()
+            .page_numbers(10)
+            .unwrap();
+        let expected = vec![
+            vec![8, 9, 10],
+            vec![1, 2],
+            vec![9],
+            vec![10, 9, 8, 7],
+            vec![1, 3, 5, 7, 9],
+            vec![2, 4, 6, 8, 10],
+            vec![1],
+        ];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn page_numbers_ok_2() {
+        let page_range_error = |input: &str| match PageRanges::parse(input).unwrap().page_numbers(5)
+        {
+            Err(Error::InValidPageRange(e)) => (e.kind, e.token, e.offset),
+            result => panic!("{input}: {result:?}"),
+        };
+        let expected = vec![
+            (
+                "1, 3-6",
+                PageRangeErrorKind::PageOverFlow {
+                    page_number: 6,
+                    max_pages: 5,
+                },
+                "6",
+                5,
+            ),
+            ("0-2", PageRangeErrorKind::PageZero, "0", 0),
+            (
+                "last-5",
+                PageRangeErrorKind::LastOffsetOverFlow {
+                    offset: 5,
+                    max_pages: 5,
+                },
+                "last-5",
+                0,
+            ),
+            (
+                "2, 1-2 !-3",
+                PageRangeErrorKind::NoPagesSelected,
+                "1-2 !-3",
+                3,
+            ),
+        ];
+        for (input, kind, token, offset) in expected {
+            assert_eq!((kind, token.to_string(), offset), page_range_error(input));
+        }
+    }
+    #[test]
+    fn page_range_error_ok_1() {
+        let input = "1, 3-6";
+        let result = PageRanges::parse(input).unwrap().page_numbers(5);
+        let Err(Error::InValidPageRange(e)) = result else {
+            panic!("{result:?}");
+        };
+        assert_eq!((5, 6), e.span(input));
+        assert_eq!(
+            "page 6 at character 6 is after the last page (5)",
+            e.to_string()
+        );
+        let input = "1,";
+        let e = parse_error(input);
+        assert_eq!((2, 2), e.span(input));
+        assert_eq!("expected a page at character 3", e.to_string());
+    }
+    #[test]
+    fn page_range_error_ok_2() {
+        // Offsets count characters, the span is in bytes
+        let input = "é, 2-ü";
+        let e = parse_error(input);
+        assert_eq!(("é", 0), (e.token.as_str(), e.offset));
+        assert_eq!((0, 2), e.span(input));
+        let input = "1, ünf";
+        let e = parse_error(input);
+        assert_eq!(3, e.offset);
+        assert_eq!((3, 5), e.span(input));
+        assert_eq!("unexpected \"ü\" at character 4", e.to_string());
+        // A no-break space is whitespace, two bytes long
+        let input = "\u{a0}1, 1 !1";
+        let result = PageRanges::parse(input).unwrap().page_numbers(5);
+        let Err(Error::InValidPageRange(e)) = result else {
+            panic!("{result:?}");
+        };
+        assert_eq!(("1 !1", 4), (e.token.as_str(), e.offset));
+        assert_eq!((5, 9), e.span(input));
+    }
+}
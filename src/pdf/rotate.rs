@@ -0,0 +1,90 @@
+use lopdf::{Document, ObjectId};
+
+use super::{DecryptDocument, Error, OrganizeDocument, Result};
+
+pub struct RotateDocument;
+
+impl RotateDocument {
+    // Clockwise rotation (0, 90, 180 or 270) of every page, in page order.
+    pub fn page_rotations_from_mem(buffer: &[u8]) -> Result<Vec<i64>> {
+        let doc = DecryptDocument::load_mem(buffer, "")?;
+
+        Ok(doc
+            .page_iter()
+            .map(|page_id| Self::_page_rotation(&doc, page_id))
+            .collect())
+    }
+
+    // Rotate pages clockwise, every item is (page number, angle) and the angle is added
+    // to the current rotation of the page. Angles must be multiples of 90.
+    pub fn rotate_pdf_from_mem(buffer: &[u8], rotations: &[(u32, i64)]) -> Result<Document> {
+        let mut doc = DecryptDocument::load_mem(buffer, "")?;
+        let pages = doc.get_pages();
+        for (page_number, angle) in rotations {
+            if angle % 90 != 0 {
+                return Err(Error::InValidRotation);
+            }
+            let page_id = *pages.get(page_number).ok_or(Error::PageNumberOverFlow)?;
+            let rotation = (Self::_page_rotation(&doc, page_id) + angle).rem_euclid(360);
+            doc.get_dictionary_mut(page_id)
+                .map_err(Error::Lopdf)?
+                .set("Rotate", rotation);
+        }
+
+        Ok(doc)
+    }
+
+    // /Rotate is inheritable, look it up on the page and then on its parents
+    fn _page_rotation(doc: &Document, page_id: ObjectId) -> i64 {
+        OrganizeDocument::inherited_page_attribute(doc, page_id, b"Rotate")
+            .and_then(|rotation| doc.dereference(rotation).ok())
+            .and_then(|(_, rotation)| rotation.as_i64().ok())
+            .map(|rotation| rotation.rem_euclid(360))
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{create_document, save_to_buffer};
+
+    #[test]
+    fn page_rotations_from_mem_ok_1() {
+        let mut doc = create_document(3);
+        let pages = doc.get_pages();
+        doc.get_dictionary_mut(pages[&2])
+            .unwrap()
+            .set("Rotate", -90);
+        let pages_id = doc
+            .catalog()
+            .unwrap()
+            .get(b"Pages")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        doc.get_dictionary_mut(pages_id).unwrap().set("Rotate", 180);
+        let buffer = save_to_buffer(&mut doc);
+        let result = RotateDocument::page_rotations_from_mem(&buffer).unwrap();
+        let expected = vec![180, 270, 180];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn rotate_pdf_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(3));
+        let mut doc =
+            RotateDocument::rotate_pdf_from_mem(&buffer, &[(1, 90), (3, -90), (3, 360)]).unwrap();
+        let buffer = save_to_buffer(&mut doc);
+        let result = RotateDocument::page_rotations_from_mem(&buffer).unwrap();
+        let expected = vec![90, 0, 270];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn rotate_pdf_from_mem_ok_2() {
+        let buffer = save_to_buffer(&mut create_document(2));
+        let result = RotateDocument::rotate_pdf_from_mem(&buffer, &[(1, 45)]);
+        assert!(matches!(result, Err(Error::InValidRotation)));
+        let result = RotateDocument::rotate_pdf_from_mem(&buffer, &[(3, 90)]);
+        assert!(matches!(result, Err(Error::PageNumberOverFlow)));
+    }
+}
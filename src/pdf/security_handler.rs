@@ -0,0 +1,348 @@
+use aes::cipher::block_padding::{NoPadding, Pkcs7};
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use lopdf::{dictionary, Dictionary, Object, StringFormat};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use super::{Error, Result};
+
+type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
+type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
+type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
+
+// Passwords are UTF-8, only the first 127 bytes are used (PDF 2.0, 7.6.4.3.3)
+const MAX_PASSWORD_LENGTH: usize = 127;
+const FILE_KEY_LENGTH: usize = 32;
+const AES_BLOCK_LENGTH: usize = 16;
+// /U and /O are a 32-byte hash, an 8-byte validation salt and an 8-byte key salt
+const VALUE_LENGTH: usize = 48;
+const HASH_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 8;
+
+// AES-256 standard security handler, revision 6 (PDF 2.0, 7.6.4)
+pub struct SecurityHandler;
+
+impl SecurityHandler {
+    pub fn is_aes256(encrypt_dict: &Dictionary) -> bool {
+        encrypt_dict.get(b"V").and_then(Object::as_i64).ok() == Some(5)
+    }
+
+    // A random file key and the encryption dictionary that unlocks it with either password,
+    // PDF 2.0, Algorithms 8, 9 and 10
+    pub fn create(
+        user_password: &str,
+        owner_password: &str,
+        flags: i32,
+    ) -> Result<([u8; FILE_KEY_LENGTH], Dictionary)> {
+        let mut file_key = [0; FILE_KEY_LENGTH];
+        Self::_fill_random(&mut file_key)?;
+        let user_password = Self::_password_bytes(user_password);
+        let owner_password = Self::_password_bytes(owner_password);
+
+        let mut user_salts = [0; SALT_LENGTH * 2];
+        Self::_fill_random(&mut user_salts)?;
+        let (user_validation_salt, user_key_salt) = user_salts.split_at(SALT_LENGTH);
+        let mut user_value = Self::_hash(user_password, user_validation_salt, &[]).to_vec();
+        user_value.extend_from_slice(&user_salts);
+        let user_key = Self::_hash(user_password, user_key_salt, &[]);
+        let user_encrypted_key = Self::_encrypt_key(&user_key, &file_key);
+
+        // The owner hashes include the whole /U value
+        let mut owner_salts = [0; SALT_LENGTH * 2];
+        Self::_fill_random(&mut owner_salts)?;
+        let (owner_validation_salt, owner_key_salt) = owner_salts.split_at(SALT_LENGTH);
+        let mut owner_value =
+            Self::_hash(owner_password, owner_validation_salt, &user_value).to_vec();
+        owner_value.extend_from_slice(&owner_salts);
+        let owner_key = Self::_hash(owner_password, owner_key_salt, &user_value);
+        let owner_encrypted_key = Self::_encrypt_key(&owner_key, &file_key);
+
+        // The permissions, "T" for encrypted metadata, "adb" and 4 random bytes
+        let mut permissions = [0; AES_BLOCK_LENGTH];
+        Self::_fill_random(&mut permissions)?;
+        permissions[..4].copy_from_slice(&(flags as u32).to_le_bytes());
+        permissions[4..8].copy_from_slice(&[0xFF; 4]);
+        permissions[8..12].copy_from_slice(b"Tadb");
+        let permissions = Self::_encrypt_key(&file_key, &permissions);
+
+        let hex_string = |value: Vec<u8>| Object::String(value, StringFormat::Hexadecimal);
+        let encrypt_dict = dictionary! {
+            "Filter" => "Standard",
+            "V" => 5,
+            "R" => 6,
+            "Length" => (FILE_KEY_LENGTH * 8) as i64,
+            "CF" => dictionary! {
+                "StdCF" => dictionary! {
+                    "CFM" => "AESV3",
+                    "AuthEvent" => "DocOpen",
+                    "Length" => FILE_KEY_LENGTH as i64,
+                },
+            },
+            "StmF" => "StdCF",
+            "StrF" => "StdCF",
+            "O" => hex_string(owner_value),
+            "U" => hex_string(user_value),
+            "OE" => hex_string(owner_encrypted_key),
+            "UE" => hex_string(user_encrypted_key),
+            "P" => flags as i64,
+            "Perms" => hex_string(permissions),
+            "EncryptMetadata" => true,
+        };
+
+        Ok((file_key, encrypt_dict))
+    }
+
+    // The file key unlocked by the owner or the user password, PDF 2.0, Algorithm 2.A
+    pub fn file_key(encrypt_dict: &Dictionary, password: &str) -> Result<[u8; FILE_KEY_LENGTH]> {
+        if !Self::_is_supported(encrypt_dict) {
+            return Err(Error::UnsupportedEncryption);
+        }
+        let value = |key: &[u8], length: usize| {
+            encrypt_dict
+                .get(key)
+                .and_then(Object::as_str)
+                .ok()
+                .filter(|value| value.len() >= length)
+                .map(|value| &value[..length])
+                .ok_or(Error::UnsupportedEncryption)
+        };
+        let owner_value = value(b"O", VALUE_LENGTH)?;
+        let user_value = value(b"U", VALUE_LENGTH)?;
+        let password = Self::_password_bytes(password);
+
+        let (key, encrypted_file_key) = if Self::_is_password(password, owner_value, user_value) {
+            let owner_key_salt = &owner_value[HASH_LENGTH + SALT_LENGTH..];
+            (
+                Self::_hash(password, owner_key_salt, user_value),
+                value(b"OE", FILE_KEY_LENGTH)?,
+            )
+        } else if Self::_is_password(password, user_value, &[]) {
+            let user_key_salt = &user_value[HASH_LENGTH + SALT_LENGTH..];
+            (
+                Self::_hash(password, user_key_salt, &[]),
+                value(b"UE", FILE_KEY_LENGTH)?,
+            )
+        } else {
+            return Err(Error::EncryptedDocument);
+        };
+        let file_key: [u8; FILE_KEY_LENGTH] =
+            Aes256CbcDec::new(GenericArray::from_slice(&key), &GenericArray::default())
+                .decrypt_padded_vec_mut::<NoPadding>(encrypted_file_key)
+                .ok()
+                .and_then(|file_key| file_key.try_into().ok())
+                .ok_or(Error::UnsupportedEncryption)?;
+
+        // A file key that does not decrypt /Perms belongs to a tampered dictionary
+        let permissions = Aes256CbcDec::new(
+            GenericArray::from_slice(&file_key),
+            &GenericArray::default(),
+        )
+        .decrypt_padded_vec_mut::<NoPadding>(value(b"Perms", AES_BLOCK_LENGTH)?)
+        .map_err(|_| Error::UnsupportedEncryption)?;
+        if &permissions[9..12] != b"adb" {
+            return Err(Error::UnsupportedEncryption);
+        }
+
+        Ok(file_key)
+    }
+
+    // Every string and stream of an object is encrypted, including the ones nested in arrays and dictionaries
+    pub fn encrypt_object(file_key: &[u8; FILE_KEY_LENGTH], object: &mut Object) -> Result<()> {
+        match object {
+            Object::String(content, format) => {
+                *content = Self::_encrypt_value(file_key, content)?;
+                // Encrypted bytes may contain line breaks that readers would normalize
+                *format = StringFormat::Hexadecimal;
+            }
+            Object::Array(array) => {
+                for item in array.iter_mut() {
+                    Self::encrypt_object(file_key, item)?;
+                }
+            }
+            Object::Dictionary(dict) => {
+                for (_, value) in dict.iter_mut() {
+                    Self::encrypt_object(file_key, value)?;
+                }
+            }
+            Object::Stream(stream) => {
+                for (_, value) in stream.dict.iter_mut() {
+                    Self::encrypt_object(file_key, value)?;
+                }
+                let content = Self::_encrypt_value(file_key, &stream.content)?;
+                stream.set_content(content);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // A string or stream that does not decrypt is kept as it is
+    pub fn decrypt_object(file_key: &[u8; FILE_KEY_LENGTH], object: &mut Object) {
+        match object {
+            Object::String(content, _) => {
+                if let Some(decrypted) = Self::_decrypt_value(file_key, content) {
+                    *content = decrypted;
+                }
+            }
+            Object::Array(array) => {
+                for item in array.iter_mut() {
+                    Self::decrypt_object(file_key, item);
+                }
+            }
+            Object::Dictionary(dict) => {
+                for (_, value) in dict.iter_mut() {
+                    Self::decrypt_object(file_key, value);
+                }
+            }
+            Object::Stream(stream) => {
+                for (_, value) in stream.dict.iter_mut() {
+                    Self::decrypt_object(file_key, value);
+                }
+                if let Some(decrypted) = Self::_decrypt_value(file_key, &stream.content) {
+                    stream.set_content(decrypted);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Revision 6 with AES-256 for both strings and streams
+    fn _is_supported(encrypt_dict: &Dictionary) -> bool {
+        let is_revision_6 = encrypt_dict.get(b"R").and_then(Object::as_i64).ok() == Some(6);
+        let is_aes256_filter = |key: &[u8]| {
+            let Ok(filter_name) = encrypt_dict.get(key).and_then(Object::as_name) else {
+                return false;
+            };
+            encrypt_dict
+                .get(b"CF")
+                .and_then(Object::as_dict)
+                .and_then(|filters| filters.get(filter_name))
+                .and_then(Object::as_dict)
+                .and_then(|filter| filter.get(b"CFM"))
+                .and_then(Object::as_name)
+                .is_ok_and(|method| method == b"AESV3")
+        };
+
+        is_revision_6 && is_aes256_filter(b"StmF") && is_aes256_filter(b"StrF")
+    }
+
+    fn _password_bytes(password: &str) -> &[u8] {
+        let password = password.as_bytes();
+        &password[..password.len().min(MAX_PASSWORD_LENGTH)]
+    }
+
+    // The first 32 bytes of /U or /O are the hash of the password and the validation salt
+    fn _is_password(password: &[u8], value: &[u8], user_value: &[u8]) -> bool {
+        let validation_salt = &value[HASH_LENGTH..HASH_LENGTH + SALT_LENGTH];
+        Self::_hash(password, validation_salt, user_value) == value[..HASH_LENGTH]
+    }
+
+    // PDF 2.0, Algorithm 2.B, `user_value` is empty for the user password
+    fn _hash(password: &[u8], salt: &[u8], user_value: &[u8]) -> [u8; HASH_LENGTH] {
+        let mut hash = Sha256::new()
+            .chain_update(password)
+            .chain_update(salt)
+            .chain_update(user_value)
+            .finalize()
+            .to_vec();
+        let mut round: u32 = 0;
+        loop {
+            let mut block: Vec<u8> = Vec::new();
+            for _ in 0..64 {
+                block.extend_from_slice(password);
+                block.extend_from_slice(&hash);
+                block.extend_from_slice(user_value);
+            }
+            let encrypted = Aes128CbcEnc::new(
+                GenericArray::from_slice(&hash[..16]),
+                GenericArray::from_slice(&hash[16..32]),
+            )
+            .encrypt_padded_vec_mut::<NoPadding>(&block);
+            // The first 16 bytes as a big-endian number modulo 3, 256 is 1 modulo 3
+            let remainder = encrypted[..16].iter().map(|byte| *byte as u32).sum::<u32>() % 3;
+            hash = match remainder {
+                0 => Sha256::digest(&encrypted).to_vec(),
+                1 => Sha384::digest(&encrypted).to_vec(),
+                _ => Sha512::digest(&encrypted).to_vec(),
+            };
+            round += 1;
+            let last_byte = encrypted.last().copied().unwrap_or(0) as u32;
+            if round >= 64 && last_byte + 32 <= round {
+                break;
+            }
+        }
+
+        let mut result = [0; HASH_LENGTH];
+        result.copy_from_slice(&hash[..HASH_LENGTH]);
+        result
+    }
+
+    // AES-256 without padding and with a zero IV, the data is a multiple of the block length
+    fn _encrypt_key(key: &[u8; 32], data: &[u8]) -> Vec<u8> {
+        Aes256CbcEnc::new(GenericArray::from_slice(key), &GenericArray::default())
+            .encrypt_padded_vec_mut::<NoPadding>(data)
+    }
+
+    // A random IV followed by the data encrypted with AES-256 in CBC mode and PKCS#7 padding
+    fn _encrypt_value(file_key: &[u8; FILE_KEY_LENGTH], data: &[u8]) -> Result<Vec<u8>> {
+        let mut iv = [0; AES_BLOCK_LENGTH];
+        Self::_fill_random(&mut iv)?;
+        let mut value = iv.to_vec();
+        value.extend(
+            Aes256CbcEnc::new(
+                GenericArray::from_slice(file_key),
+                GenericArray::from_slice(&iv),
+            )
+            .encrypt_padded_vec_mut::<Pkcs7>(data),
+        );
+
+        Ok(value)
+    }
+
+    fn _decrypt_value(file_key: &[u8; FILE_KEY_LENGTH], value: &[u8]) -> Option<Vec<u8>> {
+        if value.len() < AES_BLOCK_LENGTH {
+            return None;
+        }
+        let (iv, data) = value.split_at(AES_BLOCK_LENGTH);
+
+        Aes256CbcDec::new(
+            GenericArray::from_slice(file_key),
+            GenericArray::from_slice(iv),
+        )
+        .decrypt_padded_vec_mut::<Pkcs7>(data)
+        .ok()
+    }
+
+    fn _fill_random(buffer: &mut [u8]) -> Result<()> {
+        getrandom::getrandom(buffer).map_err(|_| Error::RandomBytesUnavailable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_key_ok_1() {
+        let (file_key, encrypt_dict) = SecurityHandler::create("user", "owner", -4).unwrap();
+        let result = SecurityHandler::file_key(&encrypt_dict, "user");
+        assert_eq!(Some(file_key), result.ok());
+        let result = SecurityHandler::file_key(&encrypt_dict, "owner");
+        assert_eq!(Some(file_key), result.ok());
+        let result = SecurityHandler::file_key(&encrypt_dict, "wrong");
+        assert!(matches!(result, Err(Error::EncryptedDocument)));
+    }
+    #[test]
+    fn encrypt_object_ok_1() {
+        let (file_key, _) = SecurityHandler::create("", "owner", -4).unwrap();
+        let mut object = Object::Array(vec![Object::string_literal("Title"), 1.into()]);
+        SecurityHandler::encrypt_object(&file_key, &mut object).unwrap();
+        let encrypted = object.as_array().unwrap()[0].as_str().unwrap();
+        // IV and one padded block
+        assert_eq!(32, encrypted.len());
+        SecurityHandler::decrypt_object(&file_key, &mut object);
+        let decrypted = object.as_array().unwrap()[0].as_str().unwrap();
+        assert_eq!(b"Title", decrypted);
+    }
+}
@@ -1,6 +1,15 @@
 use super::error::{Error, Result};
-use lopdf::Document;
+use super::{DecryptDocument, OrganizeDocument, OutlineItem, PageRanges, ThumbnailDocument};
+use lopdf::content::Content;
+use lopdf::{Document, ObjectId};
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+// Content stream operators that put ink on the page
+const TEXT_OPERATORS: [&str; 4] = ["Tj", "TJ", "'", "\""];
+const GRAPHICS_OPERATORS: [&str; 12] = [
+    "S", "s", "f", "F", "f*", "B", "B*", "b", "b*", "sh", "Do", "BI",
+];
 
 pub struct SplitDocumnet;
 
@@ -9,7 +18,7 @@ impl SplitDocumnet {
         buffer: &[u8],
         split_page_numbers_str: &str,
     ) -> Result<Vec<(Document, String)>> {
-        let document: Result<Document> = Document::load_mem(buffer).map_err(|e| Error::Lopdf(e));
+        let document: Result<Document> = DecryptDocument::load_mem(buffer, "");
 
         Self::_split_pdf(&document?, split_page_numbers_str)
     }
@@ -18,11 +27,334 @@ impl SplitDocumnet {
         buffer: &[u8],
         split_fixed_page: u32,
     ) -> Result<Vec<(Document, String)>> {
-        let document: Result<Document> = Document::load_mem(buffer).map_err(|e| Error::Lopdf(e));
+        let document: Result<Document> = DecryptDocument::load_mem(buffer, "");
 
         Self::_split_pdf_fixed_page(&document?, split_fixed_page)
     }
 
+    // One output file for every bookmark at the outline level (1 for the top level),
+    // from its page to the page before the next bookmark and named after the bookmark title.
+    // Pages before the first bookmark go to the first file.
+    pub fn split_pdf_by_outline(buffer: &[u8], level: u32) -> Result<Vec<(Document, String)>> {
+        let document: Result<Document> = DecryptDocument::load_mem(buffer, "");
+
+        Self::_split_pdf_by_outline(&document?, level)
+    }
+
+    // Start a new output file at every page whose text matches the regex, e.g. "Invoice No\. (\d+)",
+    // named after the first capture group (or the whole match without groups).
+    // Pages before the first match go to the first file.
+    pub fn split_pdf_by_text_pattern(
+        buffer: &[u8],
+        pattern: &str,
+    ) -> Result<Vec<(Document, String)>> {
+        let document: Result<Document> = DecryptDocument::load_mem(buffer, "");
+
+        Self::_split_pdf_by_text_pattern(&document?, pattern)
+    }
+
+    fn _split_pdf_by_text_pattern(
+        doc: &Document,
+        pattern: &str,
+    ) -> Result<Vec<(Document, String)>> {
+        let re = Regex::new(pattern).map_err(|_e| Error::CannotCreateRegex)?;
+        // (first page, name), pages without extractable text never match
+        let mut starts: Vec<(u32, String)> = Vec::new();
+        for page_number in doc.get_pages().into_keys() {
+            let Ok(text) = doc.extract_text(&[page_number]) else {
+                continue;
+            };
+            if let Some(captures) = re.captures(&text) {
+                let name = captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map(|name| name.as_str())
+                    .unwrap_or_default();
+                starts.push((page_number, Self::_title_to_filename(name)));
+            }
+        }
+        if starts.is_empty() {
+            return Err(Error::TextPatternNotFound);
+        }
+        starts[0].0 = 1;
+
+        Self::_split_pdf_at_starts(doc, &starts)
+    }
+
+    // Pack consecutive pages into files that are at most `max_size` bytes once saved.
+    // Fails with the page numbers of the pages that are larger than `max_size` on their own.
+    pub fn split_pdf_by_size(buffer: &[u8], max_size: usize) -> Result<Vec<(Document, String)>> {
+        let document: Result<Document> = DecryptDocument::load_mem(buffer, "");
+
+        Self::_split_pdf_by_size(&document?, max_size)
+    }
+
+    // Split at blank separator pages, e.g. the blank sheets of a batch scan, and drop them.
+    // A page is blank when its content stream paints nothing, with `is_check_images` a page
+    // that renders near-white (a scanned blank sheet) is blank as well.
+    pub fn split_pdf_by_blank_pages(
+        buffer: &[u8],
+        is_check_images: bool,
+    ) -> Result<Vec<(Document, String)>> {
+        let document = DecryptDocument::load_mem(buffer, "")?;
+        let mut blank_page_numbers: Vec<u32> = Vec::new();
+        // Pages without text that paint something, scans are images only
+        let mut page_numbers: Vec<u32> = Vec::new();
+        for (page_number, page_id) in document.get_pages() {
+            // A page whose content cannot be read is not blank, better keep it than lose it
+            let Some(operators) = Self::_page_operators(&document, page_id) else {
+                continue;
+            };
+            let is_text = operators
+                .iter()
+                .any(|operator| TEXT_OPERATORS.contains(&operator.as_str()));
+            let is_graphics = operators
+                .iter()
+                .any(|operator| GRAPHICS_OPERATORS.contains(&operator.as_str()));
+            if !is_text && !is_graphics {
+                blank_page_numbers.push(page_number);
+            } else if !is_text {
+                page_numbers.push(page_number);
+            }
+        }
+        // A line of text is only a few pixels of a render, never treat text as near-white
+        if is_check_images && !page_numbers.is_empty() {
+            // The renderer cannot read encrypted documents, render the decrypted one
+            let near_white_page_numbers = if DecryptDocument::is_encrypted_from_mem(buffer) {
+                let mut decrypted_buffer: Vec<u8> = Vec::new();
+                document
+                    .clone()
+                    .save_to(&mut decrypted_buffer)
+                    .map_err(|e| Error::Lopdf(e.into()))?;
+                ThumbnailDocument::near_white_pages_from_mem(&decrypted_buffer, &page_numbers)?
+            } else {
+                ThumbnailDocument::near_white_pages_from_mem(buffer, &page_numbers)?
+            };
+            blank_page_numbers.extend(near_white_page_numbers);
+            blank_page_numbers.sort_unstable();
+        }
+
+        Self::_split_pdf_by_blank_pages(&document, &blank_page_numbers)
+    }
+
+    fn _split_pdf_by_blank_pages(
+        doc: &Document,
+        blank_page_numbers: &[u32],
+    ) -> Result<Vec<(Document, String)>> {
+        if blank_page_numbers.is_empty() {
+            return Err(Error::BlankPageNotFound);
+        }
+        let max_pages = doc.get_pages().len() as u32;
+        // (first, last) page of every document between the separators
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        let mut first: Option<u32> = None;
+        for page_number in 1..max_pages + 1 {
+            if blank_page_numbers.contains(&page_number) {
+                if let Some(first) = first.take() {
+                    ranges.push((first, page_number - 1));
+                }
+            } else if first.is_none() {
+                first = Some(page_number);
+            }
+        }
+        if let Some(first) = first {
+            ranges.push((first, max_pages));
+        }
+        if ranges.is_empty() {
+            return Err(Error::CannotRemoveAllPages);
+        }
+
+        let mut new_split_pdfs: Vec<(Document, String)> = Vec::new();
+        for (first, last) in ranges {
+            let page_numbers: Vec<u32> = (first..last + 1).collect();
+            let document = OrganizeDocument::copy_pages(doc, &page_numbers)?;
+            new_split_pdfs.push((document, format!("{first}-{last}")));
+        }
+
+        Ok(new_split_pdfs)
+    }
+
+    fn _page_operators(doc: &Document, page_id: ObjectId) -> Option<Vec<String>> {
+        let content = doc.get_page_content(page_id).ok()?;
+        let content = Content::decode(&content).ok()?;
+
+        Some(
+            content
+                .operations
+                .into_iter()
+                .map(|operation| operation.operator)
+                .collect(),
+        )
+    }
+
+    fn _split_pdf_by_size(doc: &Document, max_size: usize) -> Result<Vec<(Document, String)>> {
+        let max_pages = doc.get_pages().len() as u32;
+        let mut new_split_pdfs: Vec<(Document, String)> = Vec::new();
+        let mut oversized_page_numbers: Vec<u32> = Vec::new();
+        let mut first = 1;
+        while first <= max_pages {
+            let (mut document, size) = Self::_extract_page_range(doc, first, first)?;
+            if size > max_size {
+                oversized_page_numbers.push(first);
+                first += 1;
+                continue;
+            }
+            // Double the range until it is too large, then binary search between the last range
+            // that fits and the first that does not, a part of n pages is saved about 2 log n times
+            let mut last = first;
+            let mut too_large: Option<u32> = None;
+            while too_large.is_none() && last < max_pages {
+                let next_last = (first + 2 * (last - first + 1) - 1).min(max_pages);
+                let (next_document, size) = Self::_extract_page_range(doc, first, next_last)?;
+                if size > max_size {
+                    too_large = Some(next_last);
+                } else {
+                    document = next_document;
+                    last = next_last;
+                }
+            }
+            if let Some(mut too_large) = too_large {
+                while too_large - last > 1 {
+                    let next_last = last + (too_large - last) / 2;
+                    let (next_document, size) = Self::_extract_page_range(doc, first, next_last)?;
+                    if size > max_size {
+                        too_large = next_last;
+                    } else {
+                        document = next_document;
+                        last = next_last;
+                    }
+                }
+            }
+            new_split_pdfs.push((document, format!("{first}-{last}")));
+            first = last + 1;
+        }
+        if !oversized_page_numbers.is_empty() {
+            return Err(Error::PagesOverSizeLimit(oversized_page_numbers));
+        }
+
+        Ok(new_split_pdfs)
+    }
+
+    // Copy of the document with only the pages first..=last, and its size once saved
+    fn _extract_page_range(doc: &Document, first: u32, last: u32) -> Result<(Document, usize)> {
+        let page_numbers: Vec<u32> = (first..last + 1).collect();
+        let mut document = OrganizeDocument::copy_pages(doc, &page_numbers)?;
+        let mut buffer: Vec<u8> = Vec::new();
+        document
+            .save_to(&mut buffer)
+            .map_err(|e| Error::Lopdf(e.into()))?;
+
+        Ok((document, buffer.len()))
+    }
+
+    fn _split_pdf_by_outline(doc: &Document, level: u32) -> Result<Vec<(Document, String)>> {
+        let pages = doc.get_pages();
+        let page_numbers: HashMap<ObjectId, u32> = pages
+            .iter()
+            .map(|(page_number, page_id)| (*page_id, *page_number))
+            .collect();
+        let mut outline_items: Vec<&OutlineItem> = Vec::new();
+        let outlines = OutlineItem::read_from_document(doc);
+        Self::_outline_items_at_level(&outlines, level, &mut outline_items);
+
+        // (first page, title), a bookmark sharing its page with the next one is dropped
+        let mut starts: Vec<(u32, String)> = outline_items
+            .into_iter()
+            .filter_map(|item| {
+                let page_number = page_numbers.get(&Self::_outline_item_page(item)?)?;
+                Some((*page_number, item.title.clone()))
+            })
+            .collect();
+        starts.sort_by_key(|(page_number, _)| *page_number);
+        starts.reverse();
+        starts.dedup_by_key(|(page_number, _)| *page_number);
+        starts.reverse();
+        if starts.is_empty() {
+            return Err(Error::OutlineLevelNotFound);
+        }
+        starts[0].0 = 1;
+        for (_, title) in starts.iter_mut() {
+            *title = Self::_title_to_filename(title);
+        }
+
+        Self::_split_pdf_at_starts(doc, &starts)
+    }
+
+    // One output file for every (first page, name) of `starts`, sorted by first page and
+    // starting at page 1, up to the page before the next start
+    fn _split_pdf_at_starts(
+        doc: &Document,
+        starts: &[(u32, String)],
+    ) -> Result<Vec<(Document, String)>> {
+        let max_pages = doc.get_pages().len() as u32;
+        let split_page_numbers: Vec<Vec<u32>> = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, (first, _))| {
+                let last = starts
+                    .get(idx + 1)
+                    .map(|(next, _)| next - 1)
+                    .unwrap_or(max_pages);
+                (*first..last + 1).collect()
+            })
+            .collect();
+        let mut new_split_pdfs: Vec<(Document, String)> = Vec::new();
+        let mut names: HashSet<String> = HashSet::new();
+        for (idx, page_numbers) in split_page_numbers.iter().enumerate() {
+            let document = OrganizeDocument::copy_pages(doc, page_numbers)?;
+            // Repeated names are numbered, e.g. "Summary_2", so the files do not overwrite each other
+            let name = &starts[idx].1;
+            let mut unique_name = name.clone();
+            for number in 2.. {
+                if names.insert(unique_name.clone()) {
+                    break;
+                }
+                unique_name = format!("{name}_{number}");
+            }
+            new_split_pdfs.push((document, unique_name));
+        }
+
+        Ok(new_split_pdfs)
+    }
+
+    fn _outline_items_at_level<'a>(
+        items: &'a [OutlineItem],
+        level: u32,
+        outline_items: &mut Vec<&'a OutlineItem>,
+    ) {
+        match level {
+            0 => {}
+            1 => outline_items.extend(items),
+            _ => items.iter().for_each(|item| {
+                Self::_outline_items_at_level(&item.children, level - 1, outline_items)
+            }),
+        }
+    }
+
+    // A bookmark only grouping its children starts at the page of its first child
+    fn _outline_item_page(item: &OutlineItem) -> Option<ObjectId> {
+        item.page_id
+            .or_else(|| item.children.iter().find_map(Self::_outline_item_page))
+    }
+
+    // Bookmark titles may contain characters that are not allowed in filenames
+    fn _title_to_filename(title: &str) -> String {
+        let filename: String = title
+            .trim()
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+                c if c.is_control() => '_',
+                c => c,
+            })
+            .collect();
+        if filename.is_empty() {
+            "untitled".to_string()
+        } else {
+            filename
+        }
+    }
+
     fn _split_pdf_fixed_page(
         doc: &Document,
         split_fixed_page: u32,
@@ -30,15 +362,12 @@ impl SplitDocumnet {
         let max_pages = doc.get_pages().len() as u32;
         let split_page_numbers =
             Self::_fixed_to_split_page_numbers_u32(max_pages, split_fixed_page)?;
-        let delete_page_numbers =
-            Self::_convert_split_to_delete_page_numbers_u32(split_page_numbers, max_pages)?;
 
         let mut new_split_pdfs: Vec<(Document, String)> = Vec::new();
         let split_str: Vec<String> =
             Self::_fixed_to_split_page_numbers_str(max_pages, split_fixed_page)?;
-        for (idx, delete_page_number) in delete_page_numbers.iter().enumerate() {
-            let mut document = doc.clone();
-            document.delete_pages(&delete_page_number);
+        for (idx, page_numbers) in split_page_numbers.iter().enumerate() {
+            let document = OrganizeDocument::copy_pages(doc, page_numbers)?;
             new_split_pdfs.push((document, split_str[idx].clone()));
         }
 
@@ -47,21 +376,111 @@ impl SplitDocumnet {
 
     fn _split_pdf(doc: &Document, split_page_numbers_str: &str) -> Result<Vec<(Document, String)>> {
         let max_pages = doc.get_pages().len() as u32;
-        let split_page_numbers =
-            Self::_string_to_split_page_numbers_u32(split_page_numbers_str, max_pages)?;
-        let delete_page_numbers =
-            Self::_convert_split_to_delete_page_numbers_u32(split_page_numbers, max_pages)?;
+        let page_ranges = PageRanges::parse(split_page_numbers_str)?;
         let mut new_split_pdfs: Vec<(Document, String)> = Vec::new();
-        let split_str: Vec<&str> = split_page_numbers_str.split(",").collect();
-        for (idx, delete_page_number) in delete_page_numbers.iter().enumerate() {
-            let mut document = doc.clone();
-            document.delete_pages(delete_page_number);
-            new_split_pdfs.push((document, split_str[idx].to_string()));
+        for item in page_ranges.items {
+            let page_numbers = item.page_numbers(max_pages)?;
+            // Keeps the selection order, e.g. "10-1" is reversed
+            let document = OrganizeDocument::copy_pages(doc, &page_numbers)?;
+            new_split_pdfs.push((document, item.text));
         }
 
         Ok(new_split_pdfs)
     }
 
+    pub fn page_numbers_from_str(page_numbers_str: &str, max_pages: u32) -> Result<Vec<u32>> {
+        let split_page_numbers = PageRanges::parse(page_numbers_str)?.page_numbers(max_pages)?;
+        let mut page_numbers: Vec<u32> = split_page_numbers.into_iter().flatten().collect();
+        page_numbers.sort_unstable();
+        page_numbers.dedup();
+
+        Ok(page_numbers)
+    }
+
+    // Build the split string from page ranges, one output file for each (first, last)
+    pub fn ranges_to_split_page_numbers_str(ranges: &[(u32, u32)]) -> String {
+        ranges
+            .iter()
+            .map(|(first, last)| {
+                if first == last {
+                    first.to_string()
+                } else {
+                    format!("{first}-{last}")
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    // The page picker edits the split string, so typed and picked files are one list.
+    // The pages of every file, none for an empty string
+    pub fn picked_files(split_page_numbers_str: &str, max_pages: u32) -> Result<Vec<Vec<u32>>> {
+        Ok(Self::_picked_items(split_page_numbers_str, max_pages)?
+            .into_iter()
+            .map(|(_, page_numbers)| page_numbers)
+            .collect())
+    }
+
+    // Remove every file with the page, the other files keep their text, e.g. "odd"
+    pub fn remove_picked_page(
+        split_page_numbers_str: &str,
+        page_number: u32,
+        max_pages: u32,
+    ) -> Result<String> {
+        Ok(Self::_picked_items(split_page_numbers_str, max_pages)?
+            .into_iter()
+            .filter(|(_, page_numbers)| !page_numbers.contains(&page_number))
+            .map(|(text, _)| text)
+            .collect::<Vec<String>>()
+            .join(", "))
+    }
+
+    // Add the pages from first to last as a new file at the end. A page is picked once only,
+    // pages that are in a file already are rejected with PickedPagesOverlap.
+    pub fn add_picked_range(
+        split_page_numbers_str: &str,
+        first: u32,
+        last: u32,
+        max_pages: u32,
+    ) -> Result<String> {
+        let (first, last) = (first.min(last), first.max(last));
+        if last > max_pages {
+            return Err(Error::PageNumberOverFlow);
+        }
+        let items = Self::_picked_items(split_page_numbers_str, max_pages)?;
+        let mut overlap: Vec<u32> = items
+            .iter()
+            .flat_map(|(_, page_numbers)| page_numbers.iter().copied())
+            .filter(|page_number| (first..=last).contains(page_number))
+            .collect();
+        if !overlap.is_empty() {
+            overlap.sort_unstable();
+            overlap.dedup();
+            return Err(Error::PickedPagesOverlap(overlap));
+        }
+        let mut texts: Vec<String> = items.into_iter().map(|(text, _)| text).collect();
+        texts.push(Self::ranges_to_split_page_numbers_str(&[(first, last)]));
+
+        Ok(texts.join(", "))
+    }
+
+    fn _picked_items(
+        split_page_numbers_str: &str,
+        max_pages: u32,
+    ) -> Result<Vec<(String, Vec<u32>)>> {
+        if split_page_numbers_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        PageRanges::parse(split_page_numbers_str)?
+            .items
+            .into_iter()
+            .map(|item| {
+                let page_numbers = item.page_numbers(max_pages)?;
+                Ok((item.text, page_numbers))
+            })
+            .collect()
+    }
+
     fn _fixed_to_split_page_numbers_u32(
         max_pages: u32,
         split_fixed_page: u32,
@@ -73,7 +492,7 @@ impl SplitDocumnet {
         let mut split_page_numbers: Vec<Vec<u32>> = Vec::new();
         let mut page_numbers: Vec<u32> = Vec::new();
         for i in 1..max_pages + 1 {
-            if i % split_fixed_page == 0 {
+            if i.is_multiple_of(split_fixed_page) {
                 page_numbers.push(i);
                 split_page_numbers.push(page_numbers);
                 page_numbers = Vec::new();
@@ -97,7 +516,7 @@ impl SplitDocumnet {
         }
         let mut split_page_numbers: Vec<String> = Vec::new();
         let max_loop = max_pages / split_fixed_page;
-        let is_remainder = max_pages % split_fixed_page != 0;
+        let is_remainder = !max_pages.is_multiple_of(split_fixed_page);
         for start in 0..max_loop {
             let start = (start * split_fixed_page) + 1;
             let end = start + split_fixed_page - 1;
@@ -112,80 +531,13 @@ impl SplitDocumnet {
         Ok(split_page_numbers)
     }
 
-    fn _convert_split_to_delete_page_numbers_u32(
-        split_page_numbers: Vec<Vec<u32>>,
-        max_pages: u32,
-    ) -> Result<Vec<Vec<u32>>> {
-        let mut delete_page_numbers: Vec<Vec<u32>> = Vec::new();
-        split_page_numbers.iter().for_each(|page_numbers| {
-            let mut delete_page_number: Vec<u32> = Vec::new();
-            let all_pages = 1..(max_pages + 1);
-            all_pages.for_each(|page| {
-                if !page_numbers.contains(&page) {
-                    delete_page_number.push(page);
-                }
-            });
-            delete_page_numbers.push(delete_page_number);
-        });
-
-        Ok(delete_page_numbers)
-    }
-
-    fn _string_to_split_page_numbers_u32(
-        split_page_numbers_str: &str,
-        max_pages: u32,
-    ) -> Result<Vec<Vec<u32>>> {
-        let check = Self::is_valid_string_split_pages(split_page_numbers_str)?;
-        if check {
-            let mut split_page_numbers: Vec<Vec<u32>> = Vec::new();
-            let split_str: Vec<&str> = split_page_numbers_str.split(",").collect();
-            for ss in split_str {
-                let split_page_number = Self::_create_page_numbers_u32(ss, max_pages)?;
-                split_page_numbers.push(split_page_number);
-            }
-            return Ok(split_page_numbers);
-        }
-        Err(Error::InValidPageNumbers)
-    }
-
-    fn _create_page_numbers_u32(split_page_number: &str, max_pages: u32) -> Result<Vec<u32>> {
-        let mut page_numbers: Vec<u32> = Vec::new();
-        if split_page_number.contains("-") {
-            let range_nums: Vec<u32> = split_page_number
-                .split("-")
-                .filter_map(|s| s.trim().parse().ok())
-                .collect();
-            let first = range_nums[0];
-            let last = range_nums[1];
-            if first > last {
-                return Err(Error::InValidPageNumbers);
-            }
-            if last > max_pages {
-                return Err(Error::PageNumberOverFlow);
-            }
-            (first..last + 1).for_each(|page_number| page_numbers.push(page_number));
-        } else {
-            let page_number = split_page_number
-                .trim()
-                .parse::<u32>()
-                .map_err(|_e| Error::InValidPageNumbers)?;
-            if page_number > max_pages {
-                return Err(Error::PageNumberOverFlow);
-            }
-            page_numbers.push(page_number);
-        }
-
-        Ok(page_numbers)
-    }
-
+    // See PageRanges for the grammar, e.g. "1, 3-5, 8-, last, odd, 1-20 !5"
     pub fn is_valid_string_split_pages(string_split_pages: &str) -> Result<bool> {
-        if string_split_pages.is_empty() {
-            return Err(Error::SplitPagesStrIsEmpty);
+        match PageRanges::parse(string_split_pages) {
+            Ok(_) => Ok(true),
+            Err(Error::InValidPageRange(_)) => Ok(false),
+            Err(e) => Err(e),
         }
-        let re = Regex::new(r"(^((\d+-\d+)|\d+)(?: *, *((\d+-\d+)|\d+))*)$")
-            .map_err(|_e| Error::CannotCreateRegex)?;
-        let check = re.is_match(string_split_pages);
-        Ok(check)
     }
 
     pub fn is_valid_string_split_fixed_pages(string_split_fixed_page: &str) -> Result<bool> {
@@ -201,6 +553,9 @@ impl SplitDocumnet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pdf::test_utils::{
+        add_outline, create_document, page_texts, save_to_buffer, set_page_content,
+    };
 
     #[test]
     fn is_valid_string_split_pages_ok_1() {
@@ -272,6 +627,322 @@ mod tests {
         assert_eq!(expected, result);
     }
     #[test]
+    fn is_valid_string_split_pages_ok_9() {
+        let split_pages = "5-, -3, last-2, odd, even, 10-1, 1-20 !5";
+        let result = SplitDocumnet::is_valid_string_split_pages(split_pages);
+        assert!(result.unwrap());
+    }
+    #[test]
+    fn split_pdf_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(5));
+        let result = SplitDocumnet::split_pdf_from_mem(&buffer, "4-2, even,1-last !3").unwrap();
+        let result: Vec<(String, Vec<String>)> = result
+            .iter()
+            .map(|(doc, name)| (name.clone(), page_texts(doc)))
+            .collect();
+        let expected: Vec<(&str, Vec<&str>)> = vec![
+            ("4-2", vec!["Page 4", "Page 3", "Page 2"]),
+            ("even", vec!["Page 2", "Page 4"]),
+            ("1-last !3", vec!["Page 1", "Page 2", "Page 4", "Page 5"]),
+        ];
+        let expected: Vec<(String, Vec<String>)> = expected
+            .into_iter()
+            .map(|(name, texts)| {
+                (
+                    name.to_string(),
+                    texts.into_iter().map(String::from).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn split_pdf_by_outline_ok_1() {
+        let mut doc = create_document(6);
+        add_outline(
+            &mut doc,
+            &[
+                ("Chapter 1", 2),
+                ("Chapter 2", 4),
+                ("Chapter 2/3", 4),
+                ("Index", 6),
+            ],
+        );
+        let buffer = save_to_buffer(&mut doc);
+        let result = SplitDocumnet::split_pdf_by_outline(&buffer, 1);
+        assert!(result.is_ok());
+        let result: Vec<(Vec<String>, String)> = result
+            .unwrap()
+            .iter()
+            .map(|(doc, title)| (page_texts(doc), title.clone()))
+            .collect();
+        let expected = vec![
+            (vec!["Page 1", "Page 2", "Page 3"], "Chapter 1"),
+            (vec!["Page 4", "Page 5"], "Chapter 2_3"),
+            (vec!["Page 6"], "Index"),
+        ];
+        let expected: Vec<(Vec<String>, String)> = expected
+            .into_iter()
+            .map(|(texts, title)| {
+                (
+                    texts.into_iter().map(String::from).collect(),
+                    title.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn split_pdf_by_outline_ok_2() {
+        let mut doc = create_document(2);
+        add_outline(&mut doc, &[("Chapter 1", 1)]);
+        let buffer = save_to_buffer(&mut doc);
+        let result = SplitDocumnet::split_pdf_by_outline(&buffer, 2);
+        assert!(matches!(result, Err(Error::OutlineLevelNotFound)));
+        let buffer = save_to_buffer(&mut create_document(2));
+        let result = SplitDocumnet::split_pdf_by_outline(&buffer, 1);
+        assert!(matches!(result, Err(Error::OutlineLevelNotFound)));
+    }
+    #[test]
+    fn split_pdf_by_outline_ok_3() {
+        // The bookmarks of every part are kept
+        let mut doc = create_document(4);
+        add_outline(&mut doc, &[("Chapter 1", 1), ("Chapter 2", 3)]);
+        let buffer = save_to_buffer(&mut doc);
+        let result = SplitDocumnet::split_pdf_by_outline(&buffer, 1).unwrap();
+        let result: Vec<Vec<String>> = result
+            .iter()
+            .map(|(doc, _)| {
+                OutlineItem::read_from_document(doc)
+                    .into_iter()
+                    .map(|item| item.title)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(vec![vec!["Chapter 1"], vec!["Chapter 2"]], result);
+    }
+    #[test]
+    fn split_pdf_by_outline_ok_4() {
+        // Repeated bookmark titles are numbered
+        let mut doc = create_document(4);
+        add_outline(
+            &mut doc,
+            &[
+                ("Chapter", 1),
+                ("Chapter", 2),
+                ("Chapter_2", 3),
+                ("Chapter", 4),
+            ],
+        );
+        let buffer = save_to_buffer(&mut doc);
+        let result = SplitDocumnet::split_pdf_by_outline(&buffer, 1).unwrap();
+        let result: Vec<&str> = result.iter().map(|(_, title)| title.as_str()).collect();
+        assert_eq!(
+            vec!["Chapter", "Chapter_2", "Chapter_2_2", "Chapter_3"],
+            result
+        );
+    }
+    #[test]
+    fn split_pdf_by_size_ok_1() {
+        let mut doc = create_document(5);
+        let buffer = save_to_buffer(&mut doc);
+        let (_, one_page_size) = SplitDocumnet::_extract_page_range(&doc, 1, 1).unwrap();
+        let (_, two_pages_size) = SplitDocumnet::_extract_page_range(&doc, 1, 2).unwrap();
+        let (_, three_pages_size) = SplitDocumnet::_extract_page_range(&doc, 1, 3).unwrap();
+        // Room for any two pages but not three
+        let max_size = (two_pages_size + three_pages_size) / 2;
+        let result = SplitDocumnet::split_pdf_by_size(&buffer, max_size);
+        assert!(result.is_ok());
+        let result: Vec<(Vec<String>, String)> = result
+            .unwrap()
+            .iter()
+            .map(|(doc, name)| (page_texts(doc), name.clone()))
+            .collect();
+        assert_eq!(3, result.len());
+        assert_eq!(vec!["Page 1", "Page 2"], result[0].0);
+        assert_eq!("1-2", result[0].1);
+        assert_eq!(vec!["Page 5"], result[2].0);
+        assert_eq!("5-5", result[2].1);
+        assert!(one_page_size < two_pages_size);
+    }
+    #[test]
+    fn split_pdf_by_size_ok_2() {
+        let mut doc = create_document(2);
+        let buffer = save_to_buffer(&mut doc);
+        let result = SplitDocumnet::split_pdf_by_size(&buffer, 10);
+        assert!(matches!(result, Err(Error::PagesOverSizeLimit(pages)) if pages == vec![1, 2]));
+    }
+    #[test]
+    fn split_pdf_by_size_ok_3() {
+        // Room for five pages, found between the doubled ranges of 4 and 8 pages
+        let mut doc = create_document(20);
+        let buffer = save_to_buffer(&mut doc);
+        let (_, five_pages_size) = SplitDocumnet::_extract_page_range(&doc, 1, 5).unwrap();
+        let (_, six_pages_size) = SplitDocumnet::_extract_page_range(&doc, 1, 6).unwrap();
+        let max_size = (five_pages_size + six_pages_size) / 2;
+        let result = SplitDocumnet::split_pdf_by_size(&buffer, max_size).unwrap();
+        let result: Vec<&str> = result.iter().map(|(_, name)| name.as_str()).collect();
+        assert_eq!(vec!["1-5", "6-10", "11-15", "16-20"], result);
+    }
+    #[test]
+    fn split_pdf_by_blank_pages_ok_1() {
+        let mut doc = create_document(7);
+        set_page_content(&mut doc, 1, b"");
+        set_page_content(&mut doc, 3, b" \n ");
+        set_page_content(&mut doc, 6, b"q 1 0 0 1 0 0 cm Q");
+        // Painted white, only blank once rendered
+        set_page_content(&mut doc, 4, b"1 g 0 0 595 842 re f");
+        let buffer = save_to_buffer(&mut doc);
+
+        let result = SplitDocumnet::split_pdf_by_blank_pages(&buffer, false).unwrap();
+        let result: Vec<(String, Vec<String>)> = result
+            .iter()
+            .map(|(doc, name)| (name.clone(), page_texts(doc)))
+            .collect();
+        let expected = vec![
+            ("2-2".to_string(), vec!["Page 2".to_string()]),
+            (
+                "4-5".to_string(),
+                vec!["".to_string(), "Page 5".to_string()],
+            ),
+            ("7-7".to_string(), vec!["Page 7".to_string()]),
+        ];
+        assert_eq!(expected, result);
+
+        let result = SplitDocumnet::split_pdf_by_blank_pages(&buffer, true).unwrap();
+        let result: Vec<&str> = result.iter().map(|(_, name)| name.as_str()).collect();
+        assert_eq!(vec!["2-2", "5-5", "7-7"], result);
+    }
+    #[test]
+    fn split_pdf_by_blank_pages_ok_2() {
+        let buffer = save_to_buffer(&mut create_document(2));
+        let result = SplitDocumnet::split_pdf_by_blank_pages(&buffer, true);
+        assert!(matches!(result, Err(Error::BlankPageNotFound)));
+
+        let mut doc = create_document(2);
+        set_page_content(&mut doc, 1, b"");
+        set_page_content(&mut doc, 2, b"");
+        let buffer = save_to_buffer(&mut doc);
+        let result = SplitDocumnet::split_pdf_by_blank_pages(&buffer, false);
+        assert!(matches!(result, Err(Error::CannotRemoveAllPages)));
+    }
+    #[test]
+    fn split_pdf_by_text_pattern_ok_1() {
+        let mut doc = create_document(5);
+        set_page_content(
+            &mut doc,
+            2,
+            b"BT /F1 12 Tf 100 600 Td (Invoice No. 1001) Tj ET",
+        );
+        set_page_content(
+            &mut doc,
+            4,
+            b"BT /F1 12 Tf 100 600 Td (Invoice No. 1002) Tj ET",
+        );
+        let buffer = save_to_buffer(&mut doc);
+
+        let result = SplitDocumnet::split_pdf_by_text_pattern(&buffer, r"Invoice No\. (\d+)");
+        let result: Vec<(String, Vec<String>)> = result
+            .unwrap()
+            .iter()
+            .map(|(doc, name)| (name.clone(), page_texts(doc)))
+            .collect();
+        // The first file starts at page 1, a new file starts at every invoice
+        let expected = vec![
+            (
+                "1001".to_string(),
+                vec![
+                    "Page 1".to_string(),
+                    "Invoice No. 1001".to_string(),
+                    "Page 3".to_string(),
+                ],
+            ),
+            (
+                "1002".to_string(),
+                vec!["Invoice No. 1002".to_string(), "Page 5".to_string()],
+            ),
+        ];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn split_pdf_by_text_pattern_ok_2() {
+        let buffer = save_to_buffer(&mut create_document(3));
+        let result = SplitDocumnet::split_pdf_by_text_pattern(&buffer, "Page [23]");
+        let result: Vec<String> = result.unwrap().into_iter().map(|(_, name)| name).collect();
+        assert_eq!(vec!["Page 2", "Page 3"], result);
+        let result = SplitDocumnet::split_pdf_by_text_pattern(&buffer, "Invoice");
+        assert!(matches!(result, Err(Error::TextPatternNotFound)));
+        let result = SplitDocumnet::split_pdf_by_text_pattern(&buffer, "(");
+        assert!(matches!(result, Err(Error::CannotCreateRegex)));
+    }
+    #[test]
+    fn outline_items_at_level_ok_1() {
+        let outlines = vec![
+            OutlineItem::new(
+                "Part 1",
+                None,
+                vec![
+                    OutlineItem::new("Chapter 1", Some((1, 0)), vec![]),
+                    OutlineItem::new("Chapter 2", Some((2, 0)), vec![]),
+                ],
+            ),
+            OutlineItem::new(
+                "Part 2",
+                Some((3, 0)),
+                vec![OutlineItem::new("Chapter 3", Some((4, 0)), vec![])],
+            ),
+        ];
+        let mut result: Vec<&OutlineItem> = Vec::new();
+        SplitDocumnet::_outline_items_at_level(&outlines, 2, &mut result);
+        let result: Vec<&str> = result.iter().map(|item| item.title.as_str()).collect();
+        assert_eq!(vec!["Chapter 1", "Chapter 2", "Chapter 3"], result);
+        assert_eq!(
+            Some((1, 0)),
+            SplitDocumnet::_outline_item_page(&outlines[0])
+        );
+    }
+    #[test]
+    fn ranges_to_split_page_numbers_str_ok_1() {
+        let result = SplitDocumnet::ranges_to_split_page_numbers_str(&[(1, 1), (2, 3), (5, 5)]);
+        let expected = "1, 2-3, 5";
+        assert_eq!(expected, result);
+        assert!(SplitDocumnet::is_valid_string_split_pages(&result).unwrap());
+    }
+    #[test]
+    fn ranges_to_split_page_numbers_str_ok_2() {
+        let result = SplitDocumnet::ranges_to_split_page_numbers_str(&[]);
+        assert!(result.is_empty());
+    }
+    #[test]
+    fn picked_files_ok_1() {
+        let result = SplitDocumnet::picked_files("1-2, odd", 5).unwrap();
+        assert_eq!(vec![vec![1, 2], vec![1, 3, 5]], result);
+        assert!(SplitDocumnet::picked_files(" ", 5).unwrap().is_empty());
+        assert!(SplitDocumnet::picked_files("1-", 5).is_ok());
+        assert!(SplitDocumnet::picked_files("1-x", 5).is_err());
+    }
+    #[test]
+    fn remove_picked_page_ok_1() {
+        let result = SplitDocumnet::remove_picked_page("1-2, odd, 4-last", 1, 5).unwrap();
+        assert_eq!("4-last", result);
+        let result = SplitDocumnet::remove_picked_page("4-last", 5, 5).unwrap();
+        assert!(result.is_empty());
+    }
+    #[test]
+    fn add_picked_range_ok_1() {
+        let result = SplitDocumnet::add_picked_range("", 3, 1, 5).unwrap();
+        assert_eq!("1-3", result);
+        let result = SplitDocumnet::add_picked_range("last, 1-3", 4, 4, 5).unwrap();
+        assert_eq!("last, 1-3, 4", result);
+    }
+    #[test]
+    fn add_picked_range_ok_2() {
+        let result = SplitDocumnet::add_picked_range("1-2, last", 2, 5, 5);
+        assert!(matches!(result, Err(Error::PickedPagesOverlap(pages)) if pages == vec![2, 5]));
+        let result = SplitDocumnet::add_picked_range("1", 2, 6, 5);
+        assert!(matches!(result, Err(Error::PageNumberOverFlow)));
+    }
+    #[test]
     fn is_valid_fixed_to_split_page_number_str_ok_1() {
         let max_pages = 5;
         let split_fixed_page = 2;
@@ -349,4 +1020,30 @@ mod tests {
         let result = SplitDocumnet::_fixed_to_split_page_numbers_str(max_pages, split_fixed_page);
         assert!(result.is_err());
     }
+    #[test]
+    fn page_numbers_from_str_ok_1() {
+        let result = SplitDocumnet::page_numbers_from_str("1-3, 7", 10);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        let expected = vec![1, 2, 3, 7];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn page_numbers_from_str_ok_2() {
+        let result = SplitDocumnet::page_numbers_from_str("5, 2-3, 3", 5);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        let expected = vec![2, 3, 5];
+        assert_eq!(expected, result);
+    }
+    #[test]
+    fn page_numbers_from_str_ok_3() {
+        let result = SplitDocumnet::page_numbers_from_str("1-11", 10);
+        assert!(result.is_err());
+    }
+    #[test]
+    fn page_numbers_from_str_ok_4() {
+        let result = SplitDocumnet::page_numbers_from_str("0", 10);
+        assert!(result.is_err());
+    }
 }
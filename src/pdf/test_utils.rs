@@ -0,0 +1,156 @@
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, text_string, Document, Object, ObjectId, Stream};
+
+// Create a document with `page_count` pages, each page shows its page number.
+pub fn create_document(page_count: u32) -> Document {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! {
+            "F1" => font_id,
+        },
+    });
+
+    let mut kids: Vec<Object> = Vec::new();
+    for page_number in 1..page_count + 1 {
+        let content = Content {
+            operations: vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), 24.into()]),
+                Operation::new("Td", vec![100.into(), 600.into()]),
+                Operation::new(
+                    "Tj",
+                    vec![Object::string_literal(format!("Page {page_number}"))],
+                ),
+                Operation::new("ET", vec![]),
+            ],
+        };
+        let content_id = doc.add_object(Stream::new(
+            dictionary! {},
+            content.encode().unwrap_or_default(),
+        ));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "Resources" => resources_id,
+        });
+        kids.push(page_id.into());
+    }
+
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => page_count,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+
+    doc
+}
+
+// Add a flat outline to the document, every item is (title, page number).
+pub fn add_outline(doc: &mut Document, items: &[(&str, u32)]) {
+    let pages = doc.get_pages();
+    let outlines_id = doc.new_object_id();
+    let item_ids: Vec<ObjectId> = items.iter().map(|_| doc.new_object_id()).collect();
+    for (idx, (title, page_number)) in items.iter().enumerate() {
+        let mut item = dictionary! {
+            "Title" => text_string(title),
+            "Parent" => outlines_id,
+            "Dest" => vec![pages[page_number].into(), "Fit".into()],
+        };
+        if idx > 0 {
+            item.set("Prev", item_ids[idx - 1]);
+        }
+        if idx + 1 < item_ids.len() {
+            item.set("Next", item_ids[idx + 1]);
+        }
+        doc.objects.insert(item_ids[idx], Object::Dictionary(item));
+    }
+    doc.objects.insert(
+        outlines_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Outlines",
+            "First" => item_ids[0],
+            "Last" => item_ids[item_ids.len() - 1],
+            "Count" => item_ids.len() as i64,
+        }),
+    );
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.set("Outlines", outlines_id);
+    }
+}
+
+// Replace the content stream of the page, e.g. with b" " for a blank page.
+pub fn set_page_content(doc: &mut Document, page_number: u32, content: &[u8]) {
+    let page_id = doc.get_pages()[&page_number];
+    let content_id = doc.add_object(Stream::new(dictionary! {}, content.to_vec()));
+    doc.get_dictionary_mut(page_id)
+        .unwrap()
+        .set("Contents", content_id);
+}
+
+pub fn save_to_buffer(doc: &mut Document) -> Vec<u8> {
+    let mut buffer: Vec<u8> = Vec::new();
+    doc.save_to(&mut buffer).unwrap();
+    buffer
+}
+
+// Text shown on every page, in page order.
+pub fn page_texts(doc: &Document) -> Vec<String> {
+    doc.get_pages()
+        .into_keys()
+        .map(|page_number| doc.extract_text(&[page_number]).unwrap())
+        .map(|text| text.trim().to_string())
+        .collect()
+}
+
+// Add a filled text field with its widget on the given page.
+pub fn add_text_field(doc: &mut Document, name: &str, value: &str, page_number: u32) {
+    let page_id = doc.get_pages()[&page_number];
+    let field_id = doc.add_object(dictionary! {
+        "Type" => "Annot",
+        "Subtype" => "Widget",
+        "FT" => "Tx",
+        "T" => text_string(name),
+        "V" => text_string(value),
+        "Rect" => vec![100.into(), 500.into(), 300.into(), 520.into()],
+        "P" => page_id,
+    });
+    if let Ok(page) = doc.get_dictionary_mut(page_id) {
+        match page.get_mut(b"Annots") {
+            Ok(Object::Array(annots)) => annots.push(field_id.into()),
+            _ => page.set("Annots", vec![field_id.into()]),
+        }
+    }
+
+    let catalog = doc.catalog_mut().unwrap();
+    match catalog.get_mut(b"AcroForm") {
+        Ok(Object::Dictionary(acro_form)) => {
+            if let Ok(Object::Array(fields)) = acro_form.get_mut(b"Fields") {
+                fields.push(field_id.into());
+            }
+        }
+        _ => catalog.set(
+            "AcroForm",
+            dictionary! {
+                "Fields" => vec![field_id.into()],
+                "DA" => Object::string_literal("/Helv 0 Tf 0 g"),
+            },
+        ),
+    }
+}
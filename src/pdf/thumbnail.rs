@@ -0,0 +1,269 @@
+use hayro::hayro_interpret::InterpreterSettings;
+use hayro::hayro_syntax::page::Page;
+use hayro::hayro_syntax::Pdf;
+use hayro::vello_cpu::color::palette::css::WHITE;
+use hayro::vello_cpu::Pixmap;
+use hayro::{render, PixmapSettings, RenderCache, RenderSettings};
+use image::codecs::jpeg::JpegEncoder;
+use image::RgbImage;
+
+use super::{Error, Result};
+
+const POINTS_PER_INCH: f32 = 72.0;
+// Keeps an A4 page under 5000 pixels wide, the renderer stops at 65535
+const MAX_RENDER_DPI: u32 = 600;
+
+// Scanned blank sheets carry dust and bleed-through, a small render is enough to tell
+const NEAR_WHITE_RENDER_WIDTH: f32 = 100.0;
+// A pixel is dark when one of its channels is below this
+const DARK_PIXEL_THRESHOLD: u8 = 200;
+// A page is near-white when at most 5 in 1000 pixels are dark
+const MAX_DARK_PIXELS_PER_MILLE: usize = 5;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum RenderImageFormat {
+    Png,
+    Jpeg,
+}
+
+pub struct RenderImageOptions {
+    pub dpi: u32,
+    pub format: RenderImageFormat,
+    // JPEG quality, 1 to 100
+    pub jpeg_quality: u8,
+}
+
+impl Default for RenderImageOptions {
+    fn default() -> Self {
+        Self {
+            dpi: 150,
+            format: RenderImageFormat::Png,
+            jpeg_quality: 85,
+        }
+    }
+}
+
+// Renders pages with hayro. The documents must not be encrypted, decrypt them with DecryptDocument first.
+pub struct ThumbnailDocument;
+
+// A document parsed once to render its thumbnails one page at a time, e.g. between UI updates
+pub struct ThumbnailRenderer {
+    pdf: Pdf,
+}
+
+impl ThumbnailRenderer {
+    pub fn new(buffer: &[u8]) -> Result<Self> {
+        let pdf = Pdf::new(buffer.to_vec()).map_err(|_| Error::CannotRenderDocument)?;
+
+        Ok(Self { pdf })
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pdf.pages().len()
+    }
+
+    // Render the page, counted from 0, to a PNG `width` pixels wide
+    pub fn render_thumbnail(&self, page_idx: usize, width: f32) -> Result<Vec<u8>> {
+        let page = self
+            .pdf
+            .pages()
+            .get(page_idx)
+            .ok_or(Error::PageNumberOverFlow)?;
+
+        ThumbnailDocument::_render_page(
+            page,
+            &RenderCache::new(),
+            &InterpreterSettings::default(),
+            &RenderSettings::default(),
+            width,
+        )
+        .into_png()
+        .map_err(|_| Error::CannotRenderDocument)
+    }
+}
+
+impl ThumbnailDocument {
+    // Render the pages to PNG or JPEG images at the DPI of the options, in the order of `page_numbers`.
+    pub fn render_images_from_mem(
+        buffer: &[u8],
+        page_numbers: &[u32],
+        options: &RenderImageOptions,
+    ) -> Result<Vec<Vec<u8>>> {
+        if !(1..=MAX_RENDER_DPI).contains(&options.dpi)
+            || !(1..=100).contains(&options.jpeg_quality)
+        {
+            return Err(Error::InValidRenderOptions);
+        }
+        let pdf = Pdf::new(buffer.to_vec()).map_err(|_| Error::CannotRenderDocument)?;
+        let cache = RenderCache::new();
+        let interpreter_settings = InterpreterSettings::default();
+        let render_settings = RenderSettings::default();
+        let pages = pdf.pages();
+
+        let mut images: Vec<Vec<u8>> = Vec::new();
+        for page_number in page_numbers {
+            let page = pages
+                .get((*page_number as usize).wrapping_sub(1))
+                .ok_or(Error::PageNumberOverFlow)?;
+            let (page_width, _) = page.render_dimensions();
+            let width = page_width * options.dpi as f32 / POINTS_PER_INCH;
+            let pixmap =
+                Self::_render_page(page, &cache, &interpreter_settings, &render_settings, width);
+            let image = match options.format {
+                RenderImageFormat::Png => pixmap.into_png().ok(),
+                RenderImageFormat::Jpeg => {
+                    // The white background leaves every pixel opaque
+                    let samples: Vec<u8> = pixmap
+                        .data()
+                        .iter()
+                        .flat_map(|pixel| [pixel.r, pixel.g, pixel.b])
+                        .collect();
+                    let mut jpeg: Vec<u8> = Vec::new();
+                    RgbImage::from_raw(pixmap.width().into(), pixmap.height().into(), samples)
+                        .and_then(|image| {
+                            JpegEncoder::new_with_quality(&mut jpeg, options.jpeg_quality)
+                                .encode_image(&image)
+                                .ok()
+                        })
+                        .map(|_| jpeg)
+                }
+            };
+            images.push(image.ok_or(Error::CannotRenderDocument)?);
+        }
+
+        Ok(images)
+    }
+
+    // The pages among `page_numbers` that render (almost) white, e.g. blank sheets of a scan.
+    pub fn near_white_pages_from_mem(buffer: &[u8], page_numbers: &[u32]) -> Result<Vec<u32>> {
+        let pdf = Pdf::new(buffer.to_vec()).map_err(|_| Error::CannotRenderDocument)?;
+        let cache = RenderCache::new();
+        let interpreter_settings = InterpreterSettings::default();
+        let render_settings = RenderSettings::default();
+        let pages = pdf.pages();
+
+        let mut near_white_page_numbers: Vec<u32> = Vec::new();
+        for page_number in page_numbers {
+            let page = pages
+                .get((*page_number as usize).wrapping_sub(1))
+                .ok_or(Error::PageNumberOverFlow)?;
+            let pixmap = Self::_render_page(
+                page,
+                &cache,
+                &interpreter_settings,
+                &render_settings,
+                NEAR_WHITE_RENDER_WIDTH,
+            );
+            let dark_pixels = pixmap
+                .data()
+                .iter()
+                .filter(|pixel| pixel.r.min(pixel.g).min(pixel.b) < DARK_PIXEL_THRESHOLD)
+                .count();
+            if dark_pixels * 1000 <= pixmap.data().len() * MAX_DARK_PIXELS_PER_MILLE {
+                near_white_page_numbers.push(*page_number);
+            }
+        }
+
+        Ok(near_white_page_numbers)
+    }
+
+    // Render the page `width` pixels wide on a white background
+    fn _render_page<'a>(
+        page: &'a Page<'a>,
+        cache: &RenderCache<'a>,
+        interpreter_settings: &InterpreterSettings,
+        render_settings: &RenderSettings,
+        width: f32,
+    ) -> Pixmap {
+        let (page_width, _) = page.render_dimensions();
+        let scale = width / page_width.max(1.0);
+        let pixmap_settings = PixmapSettings {
+            x_scale: scale,
+            y_scale: scale,
+            bg_color: WHITE,
+        };
+
+        render(
+            page,
+            cache,
+            interpreter_settings,
+            render_settings,
+            &pixmap_settings,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::test_utils::{create_document, save_to_buffer, set_page_content};
+
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    // Width and height from the IHDR chunk of a PNG
+    fn png_size(png: &[u8]) -> (u32, u32) {
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        (width, height)
+    }
+
+    #[test]
+    fn render_thumbnail_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(2));
+        let renderer = ThumbnailRenderer::new(&buffer).unwrap();
+        assert_eq!(2, renderer.page_count());
+        let result = renderer.render_thumbnail(1, 119.0).unwrap();
+        assert!(result.starts_with(PNG_SIGNATURE));
+        // A4 is 595 x 842 points
+        assert_eq!((119, 168), png_size(&result));
+        let result = renderer.render_thumbnail(2, 119.0);
+        assert!(matches!(result, Err(Error::PageNumberOverFlow)));
+    }
+    #[test]
+    fn render_thumbnail_ok_2() {
+        let result = ThumbnailRenderer::new(b"not a pdf");
+        assert!(matches!(result, Err(Error::CannotRenderDocument)));
+    }
+    #[test]
+    fn render_images_from_mem_ok_1() {
+        let buffer = save_to_buffer(&mut create_document(3));
+        let options = RenderImageOptions {
+            dpi: 36,
+            format: RenderImageFormat::Jpeg,
+            jpeg_quality: 80,
+        };
+        let result = ThumbnailDocument::render_images_from_mem(&buffer, &[3, 1], &options);
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(2, result.len());
+        let image = image::load_from_memory_with_format(&result[0], image::ImageFormat::Jpeg);
+        assert_eq!(
+            (297, 421),
+            image.map(|image| (image.width(), image.height())).unwrap()
+        );
+
+        let options = RenderImageOptions::default();
+        let result = ThumbnailDocument::render_images_from_mem(&buffer, &[2], &options);
+        assert!(result.unwrap()[0].starts_with(PNG_SIGNATURE));
+        let result = ThumbnailDocument::render_images_from_mem(&buffer, &[4], &options);
+        assert!(matches!(result, Err(Error::PageNumberOverFlow)));
+        let options = RenderImageOptions {
+            dpi: 0,
+            ..RenderImageOptions::default()
+        };
+        let result = ThumbnailDocument::render_images_from_mem(&buffer, &[1], &options);
+        assert!(matches!(result, Err(Error::InValidRenderOptions)));
+    }
+    #[test]
+    fn near_white_pages_from_mem_ok_1() {
+        let mut doc = create_document(3);
+        set_page_content(&mut doc, 1, b"0.9 g 0 0 595 842 re f");
+        set_page_content(&mut doc, 2, b"1 g 0 0 595 842 re f 0 g 10 10 2 2 re f");
+        set_page_content(&mut doc, 3, b"0 g 0 0 595 200 re f");
+        let buffer = save_to_buffer(&mut doc);
+        let result = ThumbnailDocument::near_white_pages_from_mem(&buffer, &[1, 2, 3]).unwrap();
+        assert_eq!(vec![1, 2], result);
+        let result = ThumbnailDocument::near_white_pages_from_mem(&buffer, &[4]);
+        assert!(matches!(result, Err(Error::PageNumberOverFlow)));
+    }
+}
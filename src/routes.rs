@@ -1,7 +1,9 @@
 use dioxus::prelude::*;
 
 use crate::components::BaseLayout;
-use crate::views::{Home, Merge, NotFound, Split};
+use crate::views::{
+    Compress, Home, ImagesToPdf, Merge, NotFound, Organize, PdfToImages, Protect, Rotate, Split,
+};
 
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
@@ -13,6 +15,18 @@ pub enum Route {
     Merge {},
     #[route("/split")]
     Split {},
+    #[route("/organize")]
+    Organize {},
+    #[route("/protect")]
+    Protect {},
+    #[route("/rotate")]
+    Rotate {},
+    #[route("/compress")]
+    Compress {},
+    #[route("/images-to-pdf")]
+    ImagesToPdf {},
+    #[route("/pdf-to-images")]
+    PdfToImages {},
     // PageNotFound is a catch all route that will match any route and placing the matched segments in the route field
     #[route("/:..route")]
     NotFound { route: Vec<String> },
@@ -1,27 +1,311 @@
-use web_sys::js_sys::{Array, Uint8Array};
-use web_sys::{Blob, BlobPropertyBag};
+use lopdf::Document;
+use std::collections::HashSet;
+use std::io::{Cursor, Read, Write};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::js_sys::{Array, Promise, Uint8Array};
+use web_sys::{Blob, BlobPropertyBag, Url};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
 use crate::error::{Error, Result};
 
-pub fn convert_vec_u8_to_pdf_blob(buffer: &Vec<u8>) -> Result<Blob> {
+// (path, content) of a file in a ZIP archive
+type ZipEntry = (String, Vec<u8>);
+
+// Total size of the files read from a ZIP archive, a small archive can hold gigabytes of zeros
+const MAX_ZIP_CONTENT_SIZE: u64 = 500_000_000;
+
+// e.g. "saved 12 KB", empty when nothing was saved
+pub fn saved_bytes_message(saved_bytes: usize) -> String {
+    if saved_bytes == 0 {
+        String::new()
+    } else {
+        format!("saved {} KB", saved_bytes.div_ceil(1000))
+    }
+}
+
+// e.g. "820 KB" or "12.4 MB"
+pub fn file_size_message(bytes: usize) -> String {
+    if bytes < 1_000_000 {
+        format!("{} KB", bytes.div_ceil(1000))
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+    }
+}
+
+pub fn convert_vec_u8_to_pdf_blob(buffer: &[u8]) -> Result<Blob> {
+    convert_vec_u8_to_blob(buffer, "application/pdf")
+}
+
+// Save the document into a PDF blob and return its object URL, the caller revokes it
+pub fn save_to_object_url(doc: &mut Document) -> Result<String> {
+    let mut buffer: Vec<u8> = Vec::new();
+    doc.save_to(&mut buffer)
+        .map_err(|std_io_e| Error::Pdf(std_io_e.to_string()))?;
+    let blob = convert_vec_u8_to_pdf_blob(&buffer)?;
+
+    Url::create_object_url_with_blob(&blob)
+        .map_err(|js_value| Error::JsValue(format!("{js_value:?}")))
+}
+
+// Give the browser a turn to handle events and paint between long computations.
+// Resolves on a zero timeout, or at once outside a window.
+pub async fn yield_to_browser() {
+    let promise = Promise::new(&mut |resolve, _reject| {
+        let scheduled = web_sys::window()
+            .map(|window| window.set_timeout_with_callback(&resolve).is_ok())
+            .unwrap_or_default();
+        if !scheduled {
+            let _resolve = resolve.call0(&resolve);
+        }
+    });
+    let _yielded = JsFuture::from(promise).await;
+}
+
+pub fn convert_vec_u8_to_png_blob(buffer: &[u8]) -> Result<Blob> {
+    convert_vec_u8_to_blob(buffer, "image/png")
+}
+
+pub fn convert_vec_u8_to_jpeg_blob(buffer: &[u8]) -> Result<Blob> {
+    convert_vec_u8_to_blob(buffer, "image/jpeg")
+}
+
+pub fn convert_vec_u8_to_zip_blob(buffer: &[u8]) -> Result<Blob> {
+    convert_vec_u8_to_blob(buffer, "application/zip")
+}
+
+// Pack (filename, content) files into a ZIP archive. They are stored as they are,
+// PDF, PNG and JPEG files are compressed already. A repeated name gets a number, e.g. "a_2.pdf".
+pub fn create_zip(files: &[(&str, &[u8])]) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut filenames: HashSet<String> = HashSet::new();
+    for (filename, buffer) in files {
+        let (stem, extension) = filename
+            .rsplit_once('.')
+            .map(|(stem, extension)| (stem, format!(".{extension}")))
+            .unwrap_or((filename, String::new()));
+        let mut unique_filename = filename.to_string();
+        for number in 2.. {
+            if filenames.insert(unique_filename.clone()) {
+                break;
+            }
+            unique_filename = format!("{stem}_{number}{extension}");
+        }
+        zip.start_file(unique_filename, options)
+            .map_err(|e| Error::Zip(e.to_string()))?;
+        zip.write_all(buffer)
+            .map_err(|e| Error::Zip(e.to_string()))?;
+    }
+    let cursor = zip.finish().map_err(|e| Error::Zip(e.to_string()))?;
+
+    Ok(cursor.into_inner())
+}
+
+fn convert_vec_u8_to_blob(buffer: &[u8], mime_type: &str) -> Result<Blob> {
     let uint8arr_view = unsafe { Uint8Array::view(buffer) };
     let uint8arr = Uint8Array::new(&uint8arr_view);
     let array = Array::new();
     array.push(&uint8arr.buffer());
     let blob_options = BlobPropertyBag::new();
-    blob_options.set_type("application/pdf");
+    blob_options.set_type(mime_type);
     let blob =
         Blob::new_with_u8_array_sequence_and_options(&array, &blob_options).map_err(|e| {
             if e.is_null() {
-                return Error::JsValue("Get Null From Pdf File".to_string());
+                Error::JsValue("Get Null From File".to_string())
             } else if e.is_undefined() {
-                return Error::JsValue("Get Undefined From Pdf File".to_string());
+                Error::JsValue("Get Undefined From File".to_string())
             } else if e.is_falsy() {
-                return Error::JsValue("Get False From Pdf File".to_string());
+                Error::JsValue("Get False From File".to_string())
             } else {
-                return Error::JsValue("Error Converting PDF File to Blob".to_string());
+                Error::JsValue("Error Converting File to Blob".to_string())
             }
         })?;
 
     Ok(blob)
 }
+
+// (path, content) of the files in a ZIP archive sorted by path, folders are left out.
+// Only the files whose path is kept are read, up to `max_size` bytes in total. The sizes in the archive
+// are checked before reading and the reads are limited too, as the sizes can be wrong.
+pub fn read_zip(
+    buffer: &[u8],
+    max_size: u64,
+    mut keep_path: impl FnMut(&str) -> bool,
+) -> Result<Vec<ZipEntry>> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(buffer)).map_err(|e| Error::Zip(e.to_string()))?;
+    let too_large = || {
+        Error::Zip(format!(
+            "The files in the archive are larger than {} in total",
+            file_size_message(max_size as usize)
+        ))
+    };
+    let mut files: Vec<ZipEntry> = Vec::new();
+    let mut total_size: u64 = 0;
+    for idx in 0..archive.len() {
+        let file = archive
+            .by_index(idx)
+            .map_err(|e| Error::Zip(e.to_string()))?;
+        if file.is_dir() {
+            continue;
+        }
+        let path = file
+            .name()
+            .map_err(|e| Error::Zip(e.to_string()))?
+            .to_string();
+        if !keep_path(&path) {
+            continue;
+        }
+        let remaining_size = max_size - total_size;
+        if file.size() > remaining_size {
+            return Err(too_large());
+        }
+        let mut content: Vec<u8> = Vec::new();
+        file.take(remaining_size.saturating_add(1))
+            .read_to_end(&mut content)
+            .map_err(|e| Error::Zip(e.to_string()))?;
+        if content.len() as u64 > remaining_size {
+            return Err(too_large());
+        }
+        total_size += content.len() as u64;
+        files.push((path, content));
+    }
+    files.sort_by(|(path_a, _), (path_b, _)| path_a.cmp(path_b));
+
+    Ok(files)
+}
+
+// (filename, content) of the PDF files in a ZIP archive sorted by path, and the paths of the other files.
+// macOS adds metadata under __MACOSX, it is left out without being reported.
+pub fn read_zip_pdf_files(buffer: &[u8]) -> Result<(Vec<ZipEntry>, Vec<String>)> {
+    let mut skipped_paths: Vec<String> = Vec::new();
+    let files = read_zip(buffer, MAX_ZIP_CONTENT_SIZE, |path| {
+        if path.starts_with("__MACOSX/") {
+            return false;
+        }
+        if !path.to_lowercase().ends_with(".pdf") {
+            skipped_paths.push(path.to_string());
+            return false;
+        }
+        true
+    })?;
+    skipped_paths.sort();
+    let pdf_files = files
+        .into_iter()
+        .map(|(path, content)| {
+            let filename = path.rsplit('/').next().unwrap_or(&path).to_string();
+            (filename, content)
+        })
+        .collect();
+
+    Ok((pdf_files, skipped_paths))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Entry names in the order they were written
+    fn zip_entry_names(buffer: &[u8]) -> Vec<String> {
+        let mut archive = ZipArchive::new(Cursor::new(buffer)).unwrap();
+        (0..archive.len())
+            .map(|idx| archive.by_index(idx).unwrap().name().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn create_zip_ok_1() {
+        let files: [(&str, &[u8]); 4] = [
+            ("a.pdf", b"1"),
+            ("a.pdf", b"2"),
+            ("a_2.pdf", b"3"),
+            ("README", b"4"),
+        ];
+        let buffer = create_zip(&files).unwrap();
+        assert_eq!(
+            vec!["a.pdf", "a_2.pdf", "a_2_2.pdf", "README"],
+            zip_entry_names(&buffer)
+        );
+    }
+    #[test]
+    fn create_zip_ok_2() {
+        let files: [(&str, &[u8]); 3] = [("notes", b"1"), ("notes", b"2"), ("notes", b"3")];
+        let buffer = create_zip(&files).unwrap();
+        assert_eq!(
+            vec!["notes", "notes_2", "notes_3"],
+            zip_entry_names(&buffer)
+        );
+    }
+    #[test]
+    fn read_zip_ok_1() {
+        // Sorted by path, folders are left out
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("b.pdf", options).unwrap();
+        zip.write_all(b"b").unwrap();
+        zip.add_directory("docs/", options).unwrap();
+        zip.start_file("docs/a.pdf", options).unwrap();
+        zip.write_all(b"docs a").unwrap();
+        zip.start_file("a.pdf", options).unwrap();
+        zip.write_all(b"a").unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+        let expected: Vec<(String, Vec<u8>)> = vec![
+            ("a.pdf".to_string(), b"a".to_vec()),
+            ("b.pdf".to_string(), b"b".to_vec()),
+            ("docs/a.pdf".to_string(), b"docs a".to_vec()),
+        ];
+        assert_eq!(expected, read_zip(&buffer, u64::MAX, |_| true).unwrap());
+    }
+    #[test]
+    fn read_zip_ok_2() {
+        // Round trip with create_zip
+        let files: [(&str, &[u8]); 3] = [("b.pdf", b"2"), ("a.pdf", b"1"), ("a.pdf", b"3")];
+        let buffer = create_zip(&files).unwrap();
+        let expected: Vec<(String, Vec<u8>)> = vec![
+            ("a.pdf".to_string(), b"1".to_vec()),
+            ("a_2.pdf".to_string(), b"3".to_vec()),
+            ("b.pdf".to_string(), b"2".to_vec()),
+        ];
+        assert_eq!(expected, read_zip(&buffer, u64::MAX, |_| true).unwrap());
+        assert!(matches!(
+            read_zip(b"not a zip", u64::MAX, |_| true),
+            Err(Error::Zip(_))
+        ));
+    }
+    #[test]
+    fn read_zip_ok_3() {
+        // The size limit counts the kept files only
+        let files: [(&str, &[u8]); 3] =
+            [("a.pdf", b"12345"), ("b.txt", b"123456"), ("c.pdf", b"123")];
+        let buffer = create_zip(&files).unwrap();
+        let result = read_zip(&buffer, 8, |path| path.ends_with(".pdf")).unwrap();
+        let expected: Vec<(String, Vec<u8>)> = vec![
+            ("a.pdf".to_string(), b"12345".to_vec()),
+            ("c.pdf".to_string(), b"123".to_vec()),
+        ];
+        assert_eq!(expected, result);
+        let result = read_zip(&buffer, 7, |path| path.ends_with(".pdf"));
+        assert!(matches!(result, Err(Error::Zip(_))));
+        let result = read_zip(&buffer, 10, |_| true);
+        assert!(matches!(result, Err(Error::Zip(_))));
+    }
+    #[test]
+    fn read_zip_pdf_files_ok_1() {
+        let files: [(&str, &[u8]); 5] = [
+            ("__MACOSX/docs/._a.pdf", b"metadata"),
+            ("docs/a.pdf", b"a"),
+            ("docs/notes.txt", b"notes"),
+            ("B.PDF", b"b"),
+            ("cover.png", b"png"),
+        ];
+        let buffer = create_zip(&files).unwrap();
+        let (pdf_files, skipped_paths) = read_zip_pdf_files(&buffer).unwrap();
+        let expected: Vec<(String, Vec<u8>)> = vec![
+            ("B.PDF".to_string(), b"b".to_vec()),
+            ("a.pdf".to_string(), b"a".to_vec()),
+        ];
+        assert_eq!(expected, pdf_files);
+        assert_eq!(vec!["cover.png", "docs/notes.txt"], skipped_paths);
+    }
+}
@@ -6,6 +6,12 @@ use dioxus::prelude::*;
 
 const MERGE_PDF_IMAGE: Asset = asset!("/assets/imgs/merge-cells-svgrepo-com.svg");
 const SPLIT_PDF_IMAGE: Asset = asset!("/assets/imgs/split-svgrepo-com.svg");
+const ORGANIZE_PDF_IMAGE: Asset = asset!("/assets/imgs/organize.svg");
+const ROTATE_PDF_IMAGE: Asset = asset!("/assets/imgs/rotate.svg");
+const COMPRESS_PDF_IMAGE: Asset = asset!("/assets/imgs/compress-arrows-svgrepo-com.svg");
+const IMAGES_TO_PDF_IMAGE: Asset = asset!("/assets/imgs/preview-svgrepo-com.svg");
+const PDF_TO_IMAGES_IMAGE: Asset = asset!("/assets/imgs/duplicate.svg");
+const PROTECT_PDF_IMAGE: Asset = asset!("/assets/imgs/lock.svg");
 
 #[component]
 pub fn Home() -> Element {
@@ -22,6 +28,42 @@ pub fn Home() -> Element {
             "Split PDF",
             "Separate PDFs into multiple files",
         ),
+        CardProps::new(
+            Route::Organize {},
+            ORGANIZE_PDF_IMAGE,
+            "Organize PDF",
+            "Reorder, remove or extract pages",
+        ),
+        CardProps::new(
+            Route::Rotate {},
+            ROTATE_PDF_IMAGE,
+            "Rotate PDF",
+            "Turn pages the right way up",
+        ),
+        CardProps::new(
+            Route::Compress {},
+            COMPRESS_PDF_IMAGE,
+            "Compress PDF",
+            "Shrink scans by downsampling images",
+        ),
+        CardProps::new(
+            Route::ImagesToPdf {},
+            IMAGES_TO_PDF_IMAGE,
+            "Images to PDF",
+            "Turn JPEG and PNG images into pages",
+        ),
+        CardProps::new(
+            Route::PdfToImages {},
+            PDF_TO_IMAGES_IMAGE,
+            "PDF to Images",
+            "Save pages as PNG or JPEG images",
+        ),
+        CardProps::new(
+            Route::Protect {},
+            PROTECT_PDF_IMAGE,
+            "Protect PDF",
+            "Encrypt a PDF with a password",
+        ),
     ];
     rsx! {
         Hero { title: "DIOXUS SIMPLE PDF TOOLS" }
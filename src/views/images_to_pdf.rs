@@ -0,0 +1,87 @@
+use dioxus::prelude::*;
+use dioxus_elements::HasFileData;
+
+use crate::components::{
+    input_file::{merge_input_file::upload_files, FileUploaded},
+    DropArea, Hero, ImagesToPdfAction, InputFile, MergeInputFile, ResultPdf,
+};
+
+#[component]
+pub fn ImagesToPdf() -> Element {
+    let files_uploaded: Signal<Vec<FileUploaded>> = use_signal(Vec::new);
+    let images_file_name = use_signal(String::new);
+    let images_file_object_url = use_signal(String::new);
+    let error_message = use_signal(String::new);
+    let is_display = use_memo(move || !files_uploaded.read().is_empty());
+    let mut is_drag = use_signal(|| false);
+
+    let drag_upload_file_handler = move |evt: Event<DragData>| {
+        evt.prevent_default();
+        is_drag.set(false);
+        async move {
+            if let Some(file_engine) = evt.files() {
+                upload_files(
+                    file_engine,
+                    files_uploaded,
+                    images_file_name,
+                    error_message,
+                    true,
+                )
+                .await;
+            }
+        }
+    };
+
+    rsx! {
+        Hero { title: "Images to PDF" }
+        main {
+            id: "images-to-pdf",
+            class: "flex h-screen w-full flex-col items-center",
+            ondragenter: move |evt| {
+                evt.prevent_default();
+                is_drag.set(true);
+            },
+            ondragover: move |evt| {
+                evt.prevent_default();
+                is_drag.set(true);
+            },
+            ondragleave: move |evt| {
+                evt.prevent_default();
+                is_drag.set(false);
+            },
+            ondrop: drag_upload_file_handler,
+            InputFile {
+                name: "Select JPEG or PNG Images",
+                file_input_element: rsx! {
+                    MergeInputFile {
+                        files_uploaded,
+                        merge_file_name: images_file_name,
+                        error_message,
+                        is_images_only: true,
+                    }
+                },
+            }
+            if !error_message().is_empty() {
+                p { class: "text-center text-red-500", {error_message} }
+            }
+
+            ResultPdf {
+                is_display,
+                object_url: images_file_object_url,
+                files_uploaded,
+                error_message,
+                download_element: rsx! {
+                    ImagesToPdfAction {
+                        images_file_name,
+                        images_file_object_url,
+                        files_uploaded,
+                        error_message,
+                    }
+                },
+            }
+            if is_drag() {
+                DropArea { is_drag }
+            }
+        }
+    }
+}
@@ -9,3 +9,21 @@ pub use merge::Merge;
 
 mod split;
 pub use split::Split;
+
+mod protect;
+pub use protect::Protect;
+
+mod rotate;
+pub use rotate::Rotate;
+
+mod organize;
+pub use organize::Organize;
+
+mod compress;
+pub use compress::Compress;
+
+mod images_to_pdf;
+pub use images_to_pdf::ImagesToPdf;
+
+mod pdf_to_images;
+pub use pdf_to_images::PdfToImages;
@@ -0,0 +1,27 @@
+use dioxus::prelude::*;
+
+use crate::components::{input_file::FileUploaded, PdfToImagesAction, SingleFileView};
+
+#[component]
+pub fn PdfToImages() -> Element {
+    let file_uploaded: Signal<Option<FileUploaded>> = use_signal(|| None);
+    let files_uploaded: Signal<Vec<FileUploaded>> = use_signal(Vec::new);
+    let error_message = use_signal(String::new);
+    let object_url = use_signal(String::new);
+
+    rsx! {
+        SingleFileView {
+            title: "PDF to Images",
+            id: "pdf-to-images",
+            input_name: "Select PDF File to Convert",
+            file_uploaded,
+            files_uploaded,
+            object_url,
+            error_message,
+            action_element: rsx! {
+                PdfToImagesAction { file_uploaded, files_uploaded, error_message }
+            },
+            results_zip_suffix: "images".to_string(),
+        }
+    }
+}
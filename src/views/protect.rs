@@ -0,0 +1,26 @@
+use dioxus::prelude::*;
+
+use crate::components::{input_file::FileUploaded, ProtectAction, SingleFileView};
+
+#[component]
+pub fn Protect() -> Element {
+    let file_uploaded: Signal<Option<FileUploaded>> = use_signal(|| None);
+    let files_uploaded: Signal<Vec<FileUploaded>> = use_signal(Vec::new);
+    let error_message = use_signal(String::new);
+    let object_url = use_signal(String::new);
+
+    rsx! {
+        SingleFileView {
+            title: "Protect PDF",
+            id: "protect",
+            input_name: "Select PDF File to Protect",
+            file_uploaded,
+            files_uploaded,
+            object_url,
+            error_message,
+            action_element: rsx! {
+                ProtectAction { file_uploaded, error_message }
+            },
+        }
+    }
+}
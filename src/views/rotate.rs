@@ -0,0 +1,26 @@
+use dioxus::prelude::*;
+
+use crate::components::{input_file::FileUploaded, RotateAction, SingleFileView};
+
+#[component]
+pub fn Rotate() -> Element {
+    let file_uploaded: Signal<Option<FileUploaded>> = use_signal(|| None);
+    let files_uploaded: Signal<Vec<FileUploaded>> = use_signal(Vec::new);
+    let error_message = use_signal(String::new);
+    let object_url = use_signal(String::new);
+
+    rsx! {
+        SingleFileView {
+            title: "Rotate PDF",
+            id: "rotate",
+            input_name: "Select PDF File to Rotate",
+            file_uploaded,
+            files_uploaded,
+            object_url,
+            error_message,
+            action_element: rsx! {
+                RotateAction { file_uploaded, error_message }
+            },
+        }
+    }
+}